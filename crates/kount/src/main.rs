@@ -1,24 +1,76 @@
 mod output;
+mod tui;
 
-use std::io;
+use std::fs::File;
+use std::io::{self, BufReader};
 use std::process;
 
 use clap::Parser;
+use kount_cli::args::{Command, CountArgs};
 use kount_cli::Args;
+use kount_count::walker::read_file_list;
+use kount_count::{CountConfig, CountResult};
 
-use output::{print_json, print_summary, print_table};
+use output::{clear_screen, print_delta, print_json, print_summary, print_table};
+
+fn print_result(args: &Args, result: &CountResult) -> io::Result<()> {
+    if args.json {
+        print_json(result, args.top, args.top_ext)
+    } else if args.summary {
+        print_summary(result, args.top, args.top_ext)
+    } else {
+        print_table(result, args.top, args.percent)
+    }
+}
+
+/// Build the count configuration, reading `--files-from` if it was given.
+fn build_config(count_args: &CountArgs) -> io::Result<CountConfig> {
+    let mut config = count_args.to_config();
+    if let Some(source) = &count_args.files_from {
+        let list = if source.as_os_str() == "-" {
+            read_file_list(io::stdin().lock(), count_args.null)
+        } else {
+            File::open(source)
+                .and_then(|file| read_file_list(BufReader::new(file), count_args.null))
+        };
+        let list =
+            list.map_err(|e| io::Error::new(e.kind(), format!("{}: {e}", source.display())))?;
+        config.file_list = Some(list);
+    }
+    Ok(config)
+}
 
 fn run() -> i32 {
     let args = Args::parse();
-    let config = args.to_config();
-    let result = kount_count::count(&config);
+    if let Err(e) = args.check_conflicts() {
+        e.exit();
+    }
+    let count_args = match &args.command {
+        Some(Command::Tui(tui_args)) => &tui_args.count,
+        None => &args.count,
+    };
+    let mut config = match build_config(count_args) {
+        Ok(config) => config,
+        Err(e) => {
+            eprintln!("error: {e}");
+            return 1;
+        }
+    };
+    // Lexing Rust for its test modules is only worth it where they're reported
+    config.metrics.test_modules = args.command.is_none() && (args.summary || args.json);
 
-    let print_result = if args.json {
-        print_json(&result, args.top)
-    } else if args.summary {
-        print_summary(&result, args.top)
+    let print_result = if args.command.is_some() {
+        let result = kount_count::count(&config);
+        tui::run(&result)
+    } else if args.watch {
+        kount_count::watch(&config, |result, baseline| {
+            clear_screen()?;
+            print_result(&args, result)?;
+            print_delta(result, baseline)
+        })
     } else {
-        print_table(&result, args.top)
+        let result = kount_count::count(&config);
+        print_result(&args, &result)
     };
 
     if let Err(e) = print_result {
@@ -1,28 +1,67 @@
+use std::cmp::Reverse;
 use std::io::{self, Write};
 
+use kount_count::markers::MarkerCounts;
+use kount_count::stats::Bucket;
+use kount_count::types::{ExtensionSummary, FileCount};
 use kount_count::CountResult;
 
-pub fn print_table(result: &CountResult, top: Option<usize>) -> io::Result<()> {
+/// A right-aligned numeric column of the table.
+struct Column {
+    header: &'static str,
+    value: fn(&FileCount) -> u64,
+    total: u64,
+    width: usize,
+}
+
+/// Lines and their kinds, followed by the optional metrics that were counted,
+/// in `wc` order.
+fn columns(result: &CountResult) -> Vec<Column> {
+    let column = |header: &'static str, value: fn(&FileCount) -> u64, total: Option<u64>| {
+        let total = total?;
+        let max = result.files.iter().map(value).max().unwrap_or(0).max(total);
+        Some(Column {
+            header,
+            value,
+            total,
+            width: max.to_string().len().max(header.len()),
+        })
+    };
+    [
+        column("Lines", |f| f.lines, Some(result.total_lines)),
+        column("Code", |f| f.code.unwrap_or(0), result.total_code),
+        column(
+            "Comments",
+            |f| f.comments.unwrap_or(0),
+            result.total_comments,
+        ),
+        column("Docs", |f| f.doc_lines.unwrap_or(0), result.total_doc_lines),
+        column("Blanks", |f| f.blanks.unwrap_or(0), result.total_blanks),
+        column("Words", |f| f.words.unwrap_or(0), result.total_words),
+        column("Chars", |f| f.chars.unwrap_or(0), result.total_chars),
+        column("Bytes", |f| f.bytes.unwrap_or(0), result.total_bytes),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// Width of the share-of-total-lines column in the table.
+const SHARE_WIDTH: usize = "100.0%".len();
+
+pub fn print_table(result: &CountResult, top: Option<usize>, share: bool) -> io::Result<()> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
+    let columns = columns(result);
 
-    let max_lines = result
-        .files
-        .iter()
-        .map(|f| f.lines)
-        .max()
-        .unwrap_or(0)
-        .max(result.total_lines);
-    let width = max_lines.to_string().len().max(5);
-
-    writeln!(out, "{:>width$}  File", "Lines", width = width)?;
-    writeln!(
-        out,
-        "{:\u{2500}<width$}  {:\u{2500}<30}",
-        "",
-        "",
-        width = width
-    )?;
+    for column in &columns {
+        write!(out, "{:>width$}  ", column.header, width = column.width)?;
+    }
+    if share {
+        write!(out, "{:>SHARE_WIDTH$}  ", "Share")?;
+    }
+    writeln!(out, "File")?;
+    write_rule(&mut out, &columns, share)?;
 
     let files = match top {
         Some(n) => &result.files[..n.min(result.files.len())],
@@ -30,76 +69,520 @@ pub fn print_table(result: &CountResult, top: Option<usize>) -> io::Result<()> {
     };
 
     for file in files {
-        writeln!(
-            out,
-            "{:>width$}  {}",
-            file.lines,
-            file.path.display(),
-            width = width
-        )?;
+        for column in &columns {
+            write!(
+                out,
+                "{:>width$}  ",
+                (column.value)(file),
+                width = column.width
+            )?;
+        }
+        if share {
+            write!(out, "{:>SHARE_WIDTH$}  ", percent(file.line_fraction))?;
+        }
+        writeln!(out, "{}", file.path.display())?;
     }
 
+    write_rule(&mut out, &columns, share)?;
+    for column in &columns {
+        write!(out, "{:>width$}  ", column.total, width = column.width)?;
+    }
+    if share {
+        let total = if result.total_lines == 0 { 0.0 } else { 1.0 };
+        write!(out, "{:>SHARE_WIDTH$}  ", percent(total))?;
+    }
+    writeln!(out, "total ({} files)", result.total_files)?;
+
+    write_file_markers(&mut out, result)?;
+    write_excluded(&mut out, result)?;
+    write_duplicates(&mut out, result)?;
+    write_line_length_report(&mut out, result, top)
+}
+
+/// The files with `--markers` in their comments, most first, followed by
+/// their locations if they were asked for.
+fn write_file_markers(out: &mut impl Write, result: &CountResult) -> io::Result<()> {
+    let Some(total) = &result.markers else {
+        return Ok(());
+    };
+    let found = |markers: &MarkerCounts| markers.values().sum::<u64>();
+    let mut files: Vec<&FileCount> = result
+        .files
+        .iter()
+        .filter(|f| f.markers.as_ref().is_some_and(|m| found(m) > 0))
+        .collect();
+    files.sort_by_key(|f| Reverse(f.markers.as_ref().map_or(0, found)));
+
+    let rows = files.iter().filter_map(|f| {
+        let markers = f.markers.as_ref()?;
+        Some((f.path.display().to_string(), markers))
+    });
+    write_markers(out, total, "File", rows)?;
+    write_marker_locations(out, &files)
+}
+
+/// A table of marker counts, one column per marker, labelled by `label`.
+fn write_markers<'a>(
+    out: &mut impl Write,
+    total: &MarkerCounts,
+    label: &str,
+    rows: impl Iterator<Item = (String, &'a MarkerCounts)>,
+) -> io::Result<()> {
+    writeln!(out)?;
+    let counts: Vec<String> = total
+        .iter()
+        .map(|(marker, count)| format!("{count} {marker}"))
+        .collect();
+    writeln!(out, "Markers: {}", counts.join(", "))?;
+
+    let widths: Vec<usize> = total
+        .iter()
+        .map(|(marker, count)| marker.len().max(count.to_string().len()))
+        .collect();
+    let mut rows = rows.peekable();
+    if rows.peek().is_none() {
+        return Ok(());
+    }
+    for (marker, width) in total.keys().zip(&widths) {
+        write!(out, "  {marker:>width$}")?;
+    }
+    writeln!(out, "  {label}")?;
+    for (name, markers) in rows {
+        for (marker, width) in total.keys().zip(&widths) {
+            let count = markers.get(marker).copied().unwrap_or(0);
+            write!(out, "  {count:>width$}")?;
+        }
+        writeln!(out, "  {name}")?;
+    }
+    Ok(())
+}
+
+/// Each marker as `path:line: MARKER`, as compilers report locations, or as
+/// `path:cell N:line: MARKER` in a notebook.
+fn write_marker_locations(out: &mut impl Write, files: &[&FileCount]) -> io::Result<()> {
+    let mut locations = files
+        .iter()
+        .flat_map(|f| f.marker_locations.iter().map(move |l| (f, l)))
+        .peekable();
+    if locations.peek().is_none() {
+        return Ok(());
+    }
+    writeln!(out)?;
+    for (file, location) in locations {
+        write!(out, "{}:", file.path.display())?;
+        if let Some(cell) = location.cell {
+            write!(out, "cell {cell}:")?;
+        }
+        writeln!(out, "{}: {}", location.line, location.marker)?;
+    }
+    Ok(())
+}
+
+/// Report the generated and vendored files left out of the totals.
+fn write_excluded(out: &mut impl Write, result: &CountResult) -> io::Result<()> {
+    if result.excluded.is_empty() {
+        return Ok(());
+    }
+    writeln!(out)?;
+    let kinds = [
+        (
+            "generated",
+            result.excluded.generated,
+            "; --include-generated counts them",
+        ),
+        (
+            "vendored",
+            result.excluded.vendored,
+            "; --include-vendored counts them",
+        ),
+        ("minified", result.excluded.minified, ""),
+    ];
+    for (kind, totals, hint) in kinds {
+        if totals.files > 0 {
+            writeln!(
+                out,
+                "Excluded {} {kind} files ({} lines){hint}",
+                totals.files, totals.lines
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// List the files `--dedup` left out, under the file that was counted instead.
+fn write_duplicates(out: &mut impl Write, result: &CountResult) -> io::Result<()> {
+    if result.duplicates.is_empty() {
+        return Ok(());
+    }
+    let files: usize = result.duplicates.iter().map(|g| g.duplicates.len()).sum();
+    let lines: u64 = result
+        .duplicates
+        .iter()
+        .map(|g| g.lines * g.duplicates.len() as u64)
+        .sum();
+
+    writeln!(out)?;
+    writeln!(out, "Skipped {files} duplicate files ({lines} lines):")?;
+    for group in &result.duplicates {
+        writeln!(out, "  {}", group.kept.display())?;
+        for duplicate in &group.duplicates {
+            writeln!(out, "    = {}", duplicate.display())?;
+        }
+    }
+    Ok(())
+}
+
+/// How many files the line-length report lists unless `--top` is given.
+const WORST_OFFENDERS: usize = 10;
+
+/// Print the line-length totals and the files with the most lines over the limit.
+fn write_line_length_report(
+    out: &mut impl Write,
+    result: &CountResult,
+    top: Option<usize>,
+) -> io::Result<()> {
+    let Some(total) = result.line_length else {
+        return Ok(());
+    };
+
+    writeln!(out)?;
     writeln!(
         out,
-        "{:\u{2500}<width$}  {:\u{2500}<30}",
-        "",
-        "",
-        width = width
-    )?;
-    writeln!(
-        out,
-        "{:>width$}  total ({} files)",
-        result.total_lines,
-        result.total_files,
-        width = width
+        "Line length: max {}, mean {:.1}, {} lines over {} characters",
+        total.max, total.mean, total.over_limit, total.limit
     )?;
 
+    let mut offenders: Vec<&FileCount> = result
+        .files
+        .iter()
+        .filter(|f| f.line_length.is_some_and(|l| l.over_limit > 0))
+        .collect();
+    if offenders.is_empty() {
+        return Ok(());
+    }
+    offenders.sort_by_key(|f| {
+        let lengths = f.line_length.unwrap_or_default();
+        (Reverse(lengths.over_limit), Reverse(lengths.max))
+    });
+    offenders.truncate(top.unwrap_or(WORST_OFFENDERS));
+
+    let width = total.over_limit.to_string().len().max("Over".len());
+    let max_width = total.max.to_string().len().max("Longest".len());
+    writeln!(out)?;
+    writeln!(out, "{:>width$}  {:>max_width$}  File", "Over", "Longest")?;
+    for file in offenders {
+        let lengths = file.line_length.unwrap_or_default();
+        writeln!(
+            out,
+            "{:>width$}  {:>max_width$}  {}",
+            lengths.over_limit,
+            lengths.max,
+            file.path.display()
+        )?;
+    }
     Ok(())
 }
 
-pub fn print_json(result: &CountResult, top: Option<usize>) -> io::Result<()> {
-    match top {
-        Some(n) => {
-            let truncated = CountResult {
-                files: result.files[..n.min(result.files.len())].to_vec(),
-                ..result.clone()
-            };
-            serde_json::to_writer_pretty(io::stdout().lock(), &truncated)?;
-        }
-        None => {
-            serde_json::to_writer_pretty(io::stdout().lock(), result)?;
-        }
+fn write_rule(out: &mut impl Write, columns: &[Column], share: bool) -> io::Result<()> {
+    for column in columns {
+        write!(out, "{:\u{2500}<width$}  ", "", width = column.width)?;
+    }
+    if share {
+        write!(out, "{:\u{2500}<SHARE_WIDTH$}  ", "")?;
+    }
+    writeln!(out, "{:\u{2500}<30}", "")
+}
+
+pub fn print_json(
+    result: &CountResult,
+    top: Option<usize>,
+    top_ext: Option<usize>,
+) -> io::Result<()> {
+    if top.is_none() && top_ext.is_none() {
+        serde_json::to_writer_pretty(io::stdout().lock(), result)?;
+    } else {
+        let truncated = CountResult {
+            files: match top {
+                Some(n) => result.files[..n.min(result.files.len())].to_vec(),
+                None => result.files.clone(),
+            },
+            by_extension: match top_ext {
+                Some(n) => result.top_extensions(n),
+                None => result.by_extension.clone(),
+            },
+            ..result.clone()
+        };
+        serde_json::to_writer_pretty(io::stdout().lock(), &truncated)?;
     }
     writeln!(io::stdout().lock())?;
     Ok(())
 }
 
-pub fn print_summary(result: &CountResult, _top: Option<usize>) -> io::Result<()> {
+/// `--top` limits the extensions listed unless `--top-ext` is given.
+pub fn print_summary(
+    result: &CountResult,
+    top: Option<usize>,
+    top_ext: Option<usize>,
+) -> io::Result<()> {
     let stdout = io::stdout();
     let mut out = stdout.lock();
+    let extensions = match top_ext.or(top) {
+        Some(n) => result.top_extensions(n),
+        None => result.by_extension.clone(),
+    };
 
-    writeln!(
-        out,
-        "Total: {} lines in {} files",
-        result.total_lines, result.total_files
-    )?;
+    write!(out, "Total: {} lines", result.total_lines)?;
+    let totals = [
+        (result.total_code, "code"),
+        (result.total_comments, "comments"),
+        (result.total_doc_lines, "docs"),
+        (result.total_blanks, "blanks"),
+        (result.total_words, "words"),
+        (result.total_chars, "chars"),
+        (result.total_bytes, "bytes"),
+    ];
+    for (total, unit) in metric_totals(totals) {
+        write!(out, ", {total} {unit}")?;
+    }
+    writeln!(out, " in {} files", result.total_files)?;
+    write_test_ratio(&mut out, result)?;
 
-    if !result.by_extension.is_empty() {
+    if !extensions.is_empty() {
         writeln!(out)?;
         writeln!(out, "By extension:")?;
-        for ext in &result.by_extension {
-            let label = if ext.extension.is_empty() {
-                "(no ext)".to_string()
-            } else {
-                format!(".{}", ext.extension)
-            };
+        for ext in &extensions {
+            let label = extension_label(ext);
+            write!(
+                out,
+                "  {:<8} {:>6} lines {:>6}",
+                label,
+                ext.total_lines,
+                percent(ext.line_fraction)
+            )?;
+            let totals = [
+                (ext.total_code, "code"),
+                (ext.total_comments, "comments"),
+                (ext.total_doc_lines, "docs"),
+                (ext.total_blanks, "blanks"),
+                (ext.total_words, "words"),
+                (ext.total_chars, "chars"),
+                (ext.total_bytes, "bytes"),
+            ];
+            for (total, unit) in metric_totals(totals) {
+                write!(out, "  {total:>6} {unit}")?;
+            }
             writeln!(
                 out,
-                "  {:<8} {:>6} lines  {:>3} files",
-                label, ext.total_lines, ext.file_count
+                "  {:>3} files {:>6}",
+                ext.file_count,
+                percent(ext.file_fraction)
             )?;
         }
+
+        write_documentation(&mut out, result, &extensions)?;
+        write_embedded(&mut out, result)?;
+        if let Some(total) = &result.markers {
+            let rows = extensions
+                .iter()
+                .filter_map(|ext| Some((extension_label(ext), ext.markers.as_ref()?)));
+            write_markers(&mut out, total, "Extension", rows)?;
+        }
+        write_distributions(&mut out, result, &extensions)?;
+        write_histogram(&mut out, &result.histogram)?;
+    }
+
+    write_excluded(&mut out, result)?;
+    write_duplicates(&mut out, result)?;
+    write_line_length_report(&mut out, result, top)
+}
+
+/// How much of the code is tests, as the share of all lines and per line of
+/// non-test code.
+#[allow(clippy::cast_precision_loss)]
+fn write_test_ratio(out: &mut impl Write, result: &CountResult) -> io::Result<()> {
+    if result.test_lines == 0 {
+        return Ok(());
+    }
+    let tests = result.test_lines as f64;
+    write!(
+        out,
+        "Tests: {} test lines, {} non-test lines ({} test code",
+        result.test_lines,
+        result.non_test_lines,
+        percent(tests / result.total_lines as f64)
+    )?;
+    if result.non_test_lines > 0 {
+        write!(out, ", ratio {:.2}", tests / result.non_test_lines as f64)?;
+    }
+    writeln!(out, ")")
+}
+
+fn percent(fraction: f64) -> String {
+    format!("{:.1}%", fraction * 100.0)
+}
+
+fn extension_label(ext: &ExtensionSummary) -> String {
+    if ext.rolled_up.is_some() {
+        ext.extension.clone()
+    } else if ext.extension.is_empty() {
+        "(no ext)".to_string()
+    } else {
+        format!(".{}", ext.extension)
+    }
+}
+
+fn write_distributions(
+    out: &mut impl Write,
+    result: &CountResult,
+    extensions: &[ExtensionSummary],
+) -> io::Result<()> {
+    writeln!(out)?;
+    writeln!(out, "Lines per file:")?;
+    writeln!(
+        out,
+        "  {:<8} {:>8} {:>6} {:>6} {:>6} {:>6}  Largest",
+        "", "Mean", "Median", "P90", "P99", "Max"
+    )?;
+    let rows = std::iter::once(("(all)".to_string(), &result.distribution)).chain(
+        extensions
+            .iter()
+            .map(|ext| (extension_label(ext), &ext.distribution)),
+    );
+    for (label, dist) in rows {
+        let largest = dist
+            .largest
+            .as_ref()
+            .map(|path| path.display().to_string())
+            .unwrap_or_default();
+        writeln!(
+            out,
+            "  {:<8} {:>8.1} {:>6} {:>6} {:>6} {:>6}  {}",
+            label, dist.mean, dist.median, dist.p90, dist.p99, dist.max, largest
+        )?;
+    }
+    Ok(())
+}
+
+/// Share of lines that are doc comments, when `--classify` counted them.
+fn write_documentation(
+    out: &mut impl Write,
+    result: &CountResult,
+    extensions: &[ExtensionSummary],
+) -> io::Result<()> {
+    let Some(total) = result.total_doc_lines else {
+        return Ok(());
+    };
+
+    writeln!(out)?;
+    writeln!(out, "Documentation (doc lines / lines):")?;
+    let rows = std::iter::once(("(all)".to_string(), total, result.total_lines)).chain(
+        extensions.iter().map(|ext| {
+            let docs = ext.total_doc_lines.unwrap_or(0);
+            (extension_label(ext), docs, ext.total_lines)
+        }),
+    );
+    for (label, docs, lines) in rows {
+        #[allow(clippy::cast_precision_loss)]
+        let ratio = if lines == 0 {
+            0.0
+        } else {
+            docs as f64 / lines as f64
+        };
+        writeln!(out, "  {:<8} {:>6} {:>6}", label, docs, percent(ratio))?;
+    }
+    Ok(())
+}
+
+/// The languages embedded in Markdown, HTML and component files, added up
+/// over all files.
+fn write_embedded(out: &mut impl Write, result: &CountResult) -> io::Result<()> {
+    // Language, lines, blocks, files
+    let mut totals: Vec<(&str, u64, u64, u64)> = Vec::new();
+    for embedded in result.files.iter().flat_map(|f| &f.embedded) {
+        let index = totals
+            .iter()
+            .position(|t| t.0 == embedded.language)
+            .unwrap_or_else(|| {
+                totals.push((&embedded.language, 0, 0, 0));
+                totals.len() - 1
+            });
+        let total = &mut totals[index];
+        total.1 += embedded.lines;
+        total.2 += embedded.blocks;
+        total.3 += 1;
+    }
+    if totals.is_empty() {
+        return Ok(());
+    }
+    totals.sort_by_key(|&(language, lines, ..)| (Reverse(lines), language));
+
+    writeln!(out)?;
+    writeln!(out, "Embedded languages:")?;
+    for (language, lines, blocks, files) in totals {
+        writeln!(
+            out,
+            "  {language:<12} {lines:>6} lines  {blocks:>4} blocks  {files:>3} files"
+        )?;
+    }
+    Ok(())
+}
+
+/// Width of the longest bar in the file-size histogram.
+const HISTOGRAM_WIDTH: usize = 40;
+
+fn write_histogram(out: &mut impl Write, buckets: &[Bucket]) -> io::Result<()> {
+    let most = buckets.iter().map(|b| b.files).max().unwrap_or(0);
+    if most == 0 {
+        return Ok(());
     }
 
+    writeln!(out)?;
+    writeln!(out, "File sizes (lines):")?;
+    for bucket in buckets {
+        let label = if bucket.min == bucket.max {
+            bucket.min.to_string()
+        } else {
+            format!("{}-{}", bucket.min, bucket.max)
+        };
+        // Round up so that every non-empty bucket gets a visible bar
+        let len = (bucket.files * HISTOGRAM_WIDTH as u64).div_ceil(most);
+        let bar = "\u{2588}".repeat(usize::try_from(len).unwrap_or_default());
+        writeln!(
+            out,
+            "  {:<12} {:<width$} {}",
+            label,
+            bar,
+            bucket.files,
+            width = HISTOGRAM_WIDTH
+        )?;
+    }
     Ok(())
 }
+
+/// The optional totals that were counted, paired with their unit.
+fn metric_totals<const N: usize>(
+    totals: [(Option<u64>, &'static str); N],
+) -> impl Iterator<Item = (u64, &'static str)> {
+    totals
+        .into_iter()
+        .filter_map(|(total, unit)| Some((total?, unit)))
+}
+
+pub fn clear_screen() -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+    write!(out, "\x1b[2J\x1b[H")?;
+    out.flush()
+}
+
+pub fn print_delta(result: &CountResult, baseline: &CountResult) -> io::Result<()> {
+    let stdout = io::stdout();
+    let mut out = stdout.lock();
+
+    let lines = result.total_lines.cast_signed() - baseline.total_lines.cast_signed();
+    let files = result.total_files.cast_signed() - baseline.total_files.cast_signed();
+
+    writeln!(out)?;
+    writeln!(out, "Since start: {lines:+} lines, {files:+} files")?;
+    out.flush()
+}
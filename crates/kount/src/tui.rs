@@ -0,0 +1,633 @@
+use std::cmp::Reverse;
+use std::collections::BTreeMap;
+use std::io::{self, IsTerminal};
+use std::ops::ControlFlow;
+use std::path::{Component, Path, PathBuf};
+
+use kount_count::CountResult;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::Line;
+use ratatui::widgets::{Paragraph, Row, Table, TableState, Tabs};
+use ratatui::{DefaultTerminal, Frame};
+
+const HINTS: &str = " tab view  enter open  backspace up  s sort  / filter  q quit";
+
+/// Run the interactive explorer over an already computed result.
+pub fn run(result: &CountResult) -> io::Result<()> {
+    if !io::stdout().is_terminal() {
+        return Err(io::Error::other(
+            "`kount tui` requires an interactive terminal",
+        ));
+    }
+
+    let mut terminal = ratatui::try_init()?;
+    let outcome = App::new(result).run(&mut terminal);
+    ratatui::restore();
+    outcome
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum View {
+    Directories,
+    Files,
+    Extensions,
+}
+
+impl View {
+    const ALL: [Self; 3] = [Self::Directories, Self::Files, Self::Extensions];
+
+    fn title(self) -> &'static str {
+        match self {
+            Self::Directories => "Directories",
+            Self::Files => "Files",
+            Self::Extensions => "Extensions",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL.iter().position(|v| *v == self).unwrap_or(0)
+    }
+
+    fn cycle(self, step: usize) -> Self {
+        Self::ALL[(self.index() + step) % Self::ALL.len()]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SortKey {
+    Lines,
+    Name,
+}
+
+#[derive(Debug)]
+struct Entry {
+    label: String,
+    lines: u64,
+    files: u64,
+    /// Set for rows that can be drilled into.
+    dir: Option<PathBuf>,
+}
+
+struct App {
+    /// The directory all files are in, which the tree starts from.
+    base: PathBuf,
+    /// Files with paths relative to `base`.
+    files: Vec<(PathBuf, u64)>,
+    view: View,
+    sort: SortKey,
+    dir: PathBuf,
+    filter: String,
+    editing_filter: bool,
+    entries: Vec<Entry>,
+    state: TableState,
+}
+
+impl App {
+    fn new(result: &CountResult) -> Self {
+        Self::with_files(
+            result
+                .files
+                .iter()
+                .map(|f| (normalize(&f.path), f.lines))
+                .collect(),
+        )
+    }
+
+    fn with_files(files: Vec<(PathBuf, u64)>) -> Self {
+        let base = common_dir(&files);
+        let files = files
+            .into_iter()
+            .map(|(path, lines)| match path.strip_prefix(&base) {
+                Ok(rel) => (rel.to_path_buf(), lines),
+                Err(_) => (path, lines),
+            })
+            .collect();
+        let mut app = Self {
+            base,
+            files,
+            view: View::Directories,
+            sort: SortKey::Lines,
+            dir: PathBuf::new(),
+            filter: String::new(),
+            editing_filter: false,
+            entries: Vec::new(),
+            state: TableState::default(),
+        };
+        app.rebuild();
+        app
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            if let Event::Key(key) = event::read()? {
+                if key.kind == KeyEventKind::Press && self.handle_key(key).is_break() {
+                    return Ok(());
+                }
+            }
+        }
+    }
+
+    /// Files below the current directory, with paths relative to it.
+    fn scoped_files(&self) -> impl Iterator<Item = (&Path, u64)> {
+        self.files
+            .iter()
+            .filter_map(|(path, lines)| Some((path.strip_prefix(&self.dir).ok()?, *lines)))
+    }
+
+    fn rebuild(&mut self) {
+        let mut entries: Vec<Entry> = match self.view {
+            View::Directories => {
+                let mut children: BTreeMap<String, Entry> = BTreeMap::new();
+                for (rel, lines) in self.scoped_files() {
+                    let mut components = rel.components();
+                    let Some(first) = components.next() else {
+                        continue;
+                    };
+                    let name = first.as_os_str().to_string_lossy().into_owned();
+                    let is_dir = components.next().is_some();
+                    let label = if is_dir { format!("{name}/") } else { name };
+                    let entry = children.entry(label.clone()).or_insert_with(|| Entry {
+                        label,
+                        lines: 0,
+                        files: 0,
+                        dir: is_dir.then(|| self.dir.join(first)),
+                    });
+                    entry.lines += lines;
+                    entry.files += 1;
+                }
+                children.into_values().collect()
+            }
+            View::Files => self
+                .scoped_files()
+                .map(|(rel, lines)| Entry {
+                    label: rel.display().to_string(),
+                    lines,
+                    files: 1,
+                    dir: None,
+                })
+                .collect(),
+            View::Extensions => {
+                let mut exts: BTreeMap<String, Entry> = BTreeMap::new();
+                for (rel, lines) in self.scoped_files() {
+                    let label = match rel.extension().and_then(|e| e.to_str()) {
+                        Some(ext) => format!(".{ext}"),
+                        None => "(no ext)".to_string(),
+                    };
+                    let entry = exts.entry(label.clone()).or_insert_with(|| Entry {
+                        label,
+                        lines: 0,
+                        files: 0,
+                        dir: None,
+                    });
+                    entry.lines += lines;
+                    entry.files += 1;
+                }
+                exts.into_values().collect()
+            }
+        };
+
+        if !self.filter.is_empty() {
+            let needle = self.filter.to_lowercase();
+            entries.retain(|e| e.label.to_lowercase().contains(&needle));
+        }
+
+        match self.sort {
+            SortKey::Lines => entries.sort_by(|a, b| {
+                Reverse(a.lines)
+                    .cmp(&Reverse(b.lines))
+                    .then_with(|| a.label.cmp(&b.label))
+            }),
+            SortKey::Name => entries.sort_by(|a, b| a.label.cmp(&b.label)),
+        }
+
+        self.entries = entries;
+        let selected = self.state.selected().unwrap_or(0);
+        self.state.select(if self.entries.is_empty() {
+            None
+        } else {
+            Some(selected.min(self.entries.len() - 1))
+        });
+    }
+
+    /// Apply a key press, breaking when it quits the explorer.
+    fn handle_key(&mut self, key: KeyEvent) -> ControlFlow<()> {
+        if key.modifiers.contains(KeyModifiers::CONTROL) && key.code == KeyCode::Char('c') {
+            return ControlFlow::Break(());
+        }
+
+        if self.editing_filter {
+            match key.code {
+                KeyCode::Char(c) => self.filter.push(c),
+                KeyCode::Backspace => {
+                    self.filter.pop();
+                }
+                KeyCode::Esc => {
+                    self.filter.clear();
+                    self.editing_filter = false;
+                }
+                KeyCode::Enter => self.editing_filter = false,
+                _ => return ControlFlow::Continue(()),
+            }
+            self.state.select(Some(0));
+            self.rebuild();
+            return ControlFlow::Continue(());
+        }
+
+        match key.code {
+            KeyCode::Char('q') => return ControlFlow::Break(()),
+            KeyCode::Esc if self.filter.is_empty() => return ControlFlow::Break(()),
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.rebuild();
+            }
+            KeyCode::Char('/') => self.editing_filter = true,
+            KeyCode::Tab => self.set_view(self.view.cycle(1)),
+            KeyCode::BackTab => self.set_view(self.view.cycle(View::ALL.len() - 1)),
+            KeyCode::Char('1') => self.set_view(View::Directories),
+            KeyCode::Char('2') => self.set_view(View::Files),
+            KeyCode::Char('3') => self.set_view(View::Extensions),
+            KeyCode::Char('s') => {
+                self.sort = match self.sort {
+                    SortKey::Lines => SortKey::Name,
+                    SortKey::Name => SortKey::Lines,
+                };
+                self.rebuild();
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.move_selection(1),
+            KeyCode::Up | KeyCode::Char('k') => self.move_selection(-1),
+            KeyCode::PageDown => self.move_selection(10),
+            KeyCode::PageUp => self.move_selection(-10),
+            KeyCode::Home | KeyCode::Char('g') => self.state.select_first(),
+            KeyCode::End | KeyCode::Char('G') => {
+                self.state.select(self.entries.len().checked_sub(1));
+            }
+            KeyCode::Enter | KeyCode::Right | KeyCode::Char('l') => self.enter(),
+            KeyCode::Backspace | KeyCode::Left | KeyCode::Char('h') => self.leave(),
+            _ => {}
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn set_view(&mut self, view: View) {
+        self.view = view;
+        self.state.select(Some(0));
+        self.rebuild();
+    }
+
+    fn move_selection(&mut self, delta: isize) {
+        let Some(last) = self.entries.len().checked_sub(1) else {
+            return;
+        };
+        let current = self.state.selected().unwrap_or(0);
+        self.state
+            .select(Some(current.saturating_add_signed(delta).min(last)));
+    }
+
+    fn enter(&mut self) {
+        let Some(dir) = self
+            .state
+            .selected()
+            .and_then(|i| self.entries.get(i))
+            .and_then(|e| e.dir.clone())
+        else {
+            return;
+        };
+        self.dir = dir;
+        self.filter.clear();
+        self.state.select(Some(0));
+        self.rebuild();
+    }
+
+    fn leave(&mut self) {
+        let Some(parent) = self.dir.parent().map(Path::to_path_buf) else {
+            return;
+        };
+        let previous = std::mem::replace(&mut self.dir, parent);
+        self.filter.clear();
+        self.rebuild();
+        let index = self
+            .entries
+            .iter()
+            .position(|e| e.dir.as_deref() == Some(previous.as_path()));
+        self.state.select(index.or(Some(0)));
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [tabs_area, scope_area, table_area, footer_area] = Layout::vertical([
+            Constraint::Length(1),
+            Constraint::Length(1),
+            Constraint::Min(0),
+            Constraint::Length(1),
+        ])
+        .areas(frame.area());
+
+        let tabs = Tabs::new(
+            View::ALL
+                .iter()
+                .enumerate()
+                .map(|(i, v)| format!("{} {}", i + 1, v.title())),
+        )
+        .select(self.view.index())
+        .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_widget(tabs, tabs_area);
+
+        let (lines, files) = self
+            .scoped_files()
+            .fold((0u64, 0u64), |(lines, files), (_, l)| {
+                (lines + l, files + 1)
+            });
+        let sort = match self.sort {
+            SortKey::Lines => "lines",
+            SortKey::Name => "name",
+        };
+        let dir = self.base.join(&self.dir);
+        let dir = if dir.as_os_str().is_empty() {
+            ".".to_string()
+        } else {
+            dir.display().to_string()
+        };
+        let scope = format!(" {dir}  {lines} lines in {files} files  (sorted by {sort})");
+        frame.render_widget(Paragraph::new(scope), scope_area);
+
+        let width = self
+            .entries
+            .iter()
+            .map(|e| e.lines)
+            .max()
+            .unwrap_or(0)
+            .to_string()
+            .len()
+            .max(5);
+        let header =
+            Row::new(["Lines", "Files", "Name"]).style(Style::new().add_modifier(Modifier::BOLD));
+        let rows = self.entries.iter().map(|e| {
+            Row::new([
+                format!("{:>width$}", e.lines),
+                format!("{:>5}", e.files),
+                e.label.clone(),
+            ])
+        });
+        let widths = [
+            Constraint::Length(u16::try_from(width).unwrap_or(u16::MAX)),
+            Constraint::Length(5),
+            Constraint::Fill(1),
+        ];
+        let table = Table::new(rows, widths)
+            .header(header)
+            .row_highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(table, table_area, &mut self.state);
+
+        let footer = if self.editing_filter {
+            Line::from(format!(" Filter: {}_", self.filter))
+        } else if self.filter.is_empty() {
+            Line::from(HINTS)
+        } else {
+            Line::from(format!("{HINTS}  [filter: {}]", self.filter))
+        };
+        frame.render_widget(Paragraph::new(footer), footer_area);
+    }
+}
+
+/// The deepest directory all of `files` are in, so that the tree of a walk
+/// from an absolute path doesn't start at `/`.
+fn common_dir(files: &[(PathBuf, u64)]) -> PathBuf {
+    let mut dirs = files.iter().filter_map(|(path, _)| path.parent());
+    let Some(first) = dirs.next() else {
+        return PathBuf::new();
+    };
+    let mut common: Vec<Component> = first.components().collect();
+    for dir in dirs {
+        let shared = common
+            .iter()
+            .zip(dir.components())
+            .take_while(|(a, b)| **a == *b)
+            .count();
+        common.truncate(shared);
+    }
+    common.into_iter().collect()
+}
+
+/// Drop `.` components so `./src/a.rs` and `src/a.rs` share a tree.
+fn normalize(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::ControlFlow;
+    use std::path::PathBuf;
+
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+    use ratatui::Terminal;
+
+    use super::{App, SortKey, View};
+
+    fn app() -> App {
+        App::with_files(
+            [
+                ("src/main.rs", 120),
+                ("src/tui/app.rs", 300),
+                ("src/tui/view.rs", 80),
+                ("tests/it.rs", 200),
+                ("README.md", 40),
+            ]
+            .into_iter()
+            .map(|(path, lines)| (PathBuf::from(path), lines))
+            .collect(),
+        )
+    }
+
+    fn press(app: &mut App, keys: &[KeyCode]) -> ControlFlow<()> {
+        for key in keys {
+            app.handle_key(KeyEvent::new(*key, KeyModifiers::NONE))?;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn labels(app: &App) -> Vec<&str> {
+        app.entries.iter().map(|e| e.label.as_str()).collect()
+    }
+
+    fn render(app: &mut App) -> TestBackend {
+        let mut terminal = Terminal::new(TestBackend::new(64, 8)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        terminal.backend().clone()
+    }
+
+    #[test]
+    fn starts_in_directories_by_lines() {
+        let mut app = app();
+        assert_eq!(app.view, View::Directories);
+        assert_eq!(labels(&app), ["src/", "tests/", "README.md"]);
+        insta::assert_snapshot!(render(&mut app), @r#"
+        " 1 Directories │ 2 Files │ 3 Extensions                         "
+        " .  740 lines in 5 files  (sorted by lines)                     "
+        "Lines Files Name                                                "
+        "  500     3 src/                                                "
+        "  200     1 tests/                                              "
+        "   40     1 README.md                                           "
+        "                                                                "
+        " tab view  enter open  backspace up  s sort  / filter  q quit   "
+        "#);
+    }
+
+    #[test]
+    fn switches_views() {
+        let mut app = app();
+        let _ = press(&mut app, &[KeyCode::Tab]);
+        assert_eq!(app.view, View::Files);
+        assert_eq!(
+            labels(&app),
+            [
+                "src/tui/app.rs",
+                "tests/it.rs",
+                "src/main.rs",
+                "src/tui/view.rs",
+                "README.md"
+            ]
+        );
+        let _ = press(&mut app, &[KeyCode::Char('3')]);
+        assert_eq!(app.view, View::Extensions);
+        assert_eq!(labels(&app), [".rs", ".md"]);
+        let _ = press(&mut app, &[KeyCode::BackTab, KeyCode::BackTab]);
+        assert_eq!(app.view, View::Directories);
+        let _ = press(&mut app, &[KeyCode::Char('2')]);
+        assert_eq!(app.view, View::Files);
+    }
+
+    #[test]
+    fn toggles_sort() {
+        let mut app = app();
+        let _ = press(&mut app, &[KeyCode::Char('s')]);
+        assert_eq!(app.sort, SortKey::Name);
+        assert_eq!(labels(&app), ["README.md", "src/", "tests/"]);
+        let _ = press(&mut app, &[KeyCode::Char('s')]);
+        assert_eq!(app.sort, SortKey::Lines);
+        assert_eq!(labels(&app), ["src/", "tests/", "README.md"]);
+    }
+
+    #[test]
+    fn filters_entries() {
+        let mut app = app();
+        let _ = press(
+            &mut app,
+            &[
+                KeyCode::Char('2'),
+                KeyCode::Char('/'),
+                KeyCode::Char('T'),
+                KeyCode::Char('u'),
+                KeyCode::Char('i'),
+            ],
+        );
+        assert!(app.editing_filter);
+        assert_eq!(labels(&app), ["src/tui/app.rs", "src/tui/view.rs"]);
+        insta::assert_snapshot!(render(&mut app), @r#"
+        " 1 Directories │ 2 Files │ 3 Extensions                         "
+        " .  740 lines in 5 files  (sorted by lines)                     "
+        "Lines Files Name                                                "
+        "  300     1 src/tui/app.rs                                      "
+        "   80     1 src/tui/view.rs                                     "
+        "                                                                "
+        "                                                                "
+        " Filter: Tui_                                                   "
+        "#);
+
+        // Enter keeps the filter, Esc then clears it
+        let _ = press(&mut app, &[KeyCode::Enter]);
+        assert!(!app.editing_filter);
+        assert_eq!(app.filter, "Tui");
+        assert!(press(&mut app, &[KeyCode::Esc]).is_continue());
+        assert_eq!(app.entries.len(), 5);
+
+        // Esc while typing drops the filter too
+        let _ = press(&mut app, &[KeyCode::Char('/'), KeyCode::Char('x')]);
+        assert!(app.entries.is_empty());
+        let _ = press(&mut app, &[KeyCode::Esc]);
+        assert!(!app.editing_filter);
+        assert_eq!(app.entries.len(), 5);
+    }
+
+    #[test]
+    fn enters_and_leaves_directories() {
+        let mut app = app();
+        let _ = press(&mut app, &[KeyCode::Enter]);
+        assert_eq!(app.dir, PathBuf::from("src"));
+        assert_eq!(labels(&app), ["tui/", "main.rs"]);
+
+        let _ = press(&mut app, &[KeyCode::Enter]);
+        assert_eq!(app.dir, PathBuf::from("src/tui"));
+        insta::assert_snapshot!(render(&mut app), @r#"
+        " 1 Directories │ 2 Files │ 3 Extensions                         "
+        " src/tui  380 lines in 2 files  (sorted by lines)               "
+        "Lines Files Name                                                "
+        "  300     1 app.rs                                              "
+        "   80     1 view.rs                                             "
+        "                                                                "
+        "                                                                "
+        " tab view  enter open  backspace up  s sort  / filter  q quit   "
+        "#);
+
+        // Files can't be entered
+        let _ = press(&mut app, &[KeyCode::Enter]);
+        assert_eq!(app.dir, PathBuf::from("src/tui"));
+
+        // Leaving selects the directory we came from
+        let _ = press(&mut app, &[KeyCode::Backspace]);
+        assert_eq!(app.dir, PathBuf::from("src"));
+        assert_eq!(app.state.selected(), Some(0));
+        let _ = press(&mut app, &[KeyCode::Backspace]);
+        assert_eq!(app.dir, PathBuf::new());
+        assert_eq!(app.state.selected(), Some(0));
+
+        let _ = press(&mut app, &[KeyCode::Down, KeyCode::Enter]);
+        assert_eq!(app.dir, PathBuf::from("tests"));
+        let _ = press(&mut app, &[KeyCode::Left]);
+        assert_eq!(labels(&app)[app.state.selected().unwrap()], "tests/");
+    }
+
+    #[test]
+    fn starts_below_absolute_roots() {
+        let mut app = App::with_files(
+            [
+                ("/home/me/proj/src/main.rs", 10),
+                ("/home/me/proj/src/lib.rs", 20),
+                ("/home/me/proj/tests/it.rs", 5),
+            ]
+            .into_iter()
+            .map(|(path, lines)| (PathBuf::from(path), lines))
+            .collect(),
+        );
+        assert_eq!(labels(&app), ["src/", "tests/"]);
+        let _ = press(&mut app, &[KeyCode::Enter]);
+        assert_eq!(labels(&app), ["lib.rs", "main.rs"]);
+        insta::assert_snapshot!(render(&mut app), @r#"
+        " 1 Directories │ 2 Files │ 3 Extensions                         "
+        " /home/me/proj/src  30 lines in 2 files  (sorted by lines)      "
+        "Lines Files Name                                                "
+        "   20     1 lib.rs                                              "
+        "   10     1 main.rs                                             "
+        "                                                                "
+        "                                                                "
+        " tab view  enter open  backspace up  s sort  / filter  q quit   "
+        "#);
+        let _ = press(&mut app, &[KeyCode::Backspace, KeyCode::Backspace]);
+        assert_eq!(labels(&app), ["src/", "tests/"]);
+    }
+
+    #[test]
+    fn quits() {
+        assert!(press(&mut app(), &[KeyCode::Char('q')]).is_break());
+        assert!(press(&mut app(), &[KeyCode::Esc]).is_break());
+        let mut app = app();
+        let ctrl_c = KeyEvent::new(KeyCode::Char('c'), KeyModifiers::CONTROL);
+        assert!(app.handle_key(ctrl_c).is_break());
+        // A `q` typed into the filter doesn't quit
+        assert!(press(&mut app, &[KeyCode::Char('/'), KeyCode::Char('q')]).is_continue());
+    }
+}
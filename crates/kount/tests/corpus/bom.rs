@@ -0,0 +1,5 @@
+﻿// kount: lines=5 code=2 comments=3 docs=1 blanks=0
+// Saved with a UTF-8 byte order mark before the first comment
+/// Item docs
+fn main() {
+}
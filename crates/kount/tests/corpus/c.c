@@ -0,0 +1,13 @@
+// kount: lines=13 code=7 comments=5 docs=0 blanks=1
+#include <stdio.h>
+
+/* block comment
+   spanning lines */
+int main(void) {
+    const char *s = "/* not a comment */ // nor this";
+    char c = '"';
+    // comment
+    const char *e = "escaped \" quote /*";
+    // comment
+    return 0; /* trailing */
+}
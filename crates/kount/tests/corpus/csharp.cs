@@ -0,0 +1,12 @@
+// kount: lines=12 code=7 comments=5 docs=1 blanks=0
+/// <summary>XML doc comment</summary>
+class Program {
+    static string Path = @"C:\temp\";
+    // comment after a verbatim string ending in a backslash
+    static string Quoted = @"say ""hi"" // not a comment";
+    // comment
+    static string Multi = @"first
+// inside the verbatim string
+last";
+    /* block */
+}
@@ -0,0 +1,8 @@
+// kount: lines=8 code=5 comments=3 docs=0 blanks=0
+/* outer /* nested */ outer */
+void main() {
+  var s = '''
+// inside a multi-line string
+''';
+  // comment
+}
@@ -0,0 +1,7 @@
+# kount: lines=7 code=5 comments=2 docs=0 blanks=0
+defmodule M do
+  @doc """
+  # inside a heredoc
+  """
+  # comment
+end
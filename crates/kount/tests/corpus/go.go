@@ -0,0 +1,16 @@
+// kount: lines=16 code=9 comments=5 docs=0 blanks=2
+package main
+
+var query = `
+// not a comment inside a raw string
+/* nor this */
+`
+
+/* a block
+comment */
+func main() {
+	r := '"' // a rune holding a quote
+	// comment
+	s := "\"/*"
+	// comment
+}
@@ -0,0 +1,9 @@
+-- kount: lines=9 code=2 comments=7 docs=1 blanks=0
+-- | Entry point docs
+{- outer {- nested -} still outer -}
+main :: IO ()
+main = putStrLn "-- not a comment {- nor this"
+-- comment
+{-
+  multi-line
+-}
@@ -0,0 +1,8 @@
+// kount: lines=8 code=6 comments=2 docs=0 blanks=0
+class Main {
+    String block = """
+        /* inside a text block
+        """;
+    // comment
+    char c = '/'; /* block */
+}
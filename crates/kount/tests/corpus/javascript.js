@@ -0,0 +1,13 @@
+// kount: lines=13 code=8 comments=5 docs=3 blanks=0
+const url = "http://example.com";
+const tpl = `
+// inside a template literal
+${url} /* not a comment */
+`;
+/**
+ * JSDoc block
+ */
+function f() {
+  return '/*'; // the quote holds a comment opener
+}
+// comment
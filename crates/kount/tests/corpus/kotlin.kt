@@ -0,0 +1,7 @@
+// kount: lines=7 code=3 comments=4 docs=0 blanks=0
+/* outer /* nested */ still outer
+*/
+val raw = """C:\path\"""
+// comment after a raw string ending in a backslash
+val s = "a \" // b"
+fun main() {}
@@ -0,0 +1,9 @@
+-- kount: lines=9 code=4 comments=5 docs=1 blanks=0
+--[[ a block
+comment ]]
+local s = [[
+-- inside a long string
+]]
+local t = "--[[ not a comment" -- trailing
+-- comment
+--- LuaDoc comment
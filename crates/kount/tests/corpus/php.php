@@ -0,0 +1,5 @@
+<?php // kount: lines=5 code=3 comments=2 docs=0 blanks=0
+# hash comment
+$s = "# not a comment // nor this";
+/* block */
+echo $s;
@@ -0,0 +1,18 @@
+# kount: lines=18 code=9 comments=8 docs=5 blanks=1
+def greet(name):
+    """Docstring with a # that is not a comment.
+
+    # still the docstring
+    """
+    text = "a # inside a string"
+    # a real comment
+    raw = r"C:\path\" # still inside the raw string"
+    single = '''
+# not a comment, inside a triple-quoted string
+'''
+    return f"{name!r} # {text}"
+
+class Greeter:
+    '''Single-quoted docstring'''
+    # comment
+    pass
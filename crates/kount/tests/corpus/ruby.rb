@@ -0,0 +1,6 @@
+# kount: lines=6 code=1 comments=5 docs=0 blanks=0
+=begin
+a block comment
+=end
+puts "#{1 + 1} # not a comment"
+# comment
@@ -0,0 +1,31 @@
+// kount: lines=31 code=15 comments=15 docs=4 blanks=1
+//! Crate docs
+/// Item docs
+//// not a doc comment
+/** block docs
+ * continued */
+/**/
+/* Outer comment /* nested */
+   still in the outer comment */
+fn main() {
+    let url = "https://example.com // not a comment";
+    // a comment after a string holding //
+    let raw = r#"a "quoted" /* not a comment"#;
+    let quote = '"';
+    // a comment after a char literal holding a quote
+    let lifetime: &'static str = "'";
+    // a comment after a lifetime
+    let bytes = br"\";
+    // a comment after a raw byte string ending in a backslash
+    let multi = "first line
+// inside the string
+
+last line";
+
+    /* a comment */ let after = 1;
+    let text = r##"
+        "# is not the end
+    "##;
+    /* unterminated /* nested */ still a comment
+    */
+}
@@ -0,0 +1,11 @@
+// kount: lines=11 code=7 comments=4 docs=0 blanks=0
+object Main {
+  val c = '"'
+  // comment after a char literal holding a quote
+  val sym = 'sym
+  /* outer /* nested */
+  */
+  val s = """multi
+// inside
+"""
+}
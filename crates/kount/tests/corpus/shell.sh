@@ -0,0 +1,6 @@
+# kount: lines=6 code=4 comments=2 docs=0 blanks=0
+echo "# not a comment"
+msg='it is # not a comment
+# still inside the single-quoted string'
+# comment
+echo "$msg" # trailing
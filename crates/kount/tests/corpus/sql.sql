@@ -0,0 +1,5 @@
+-- kount: lines=5 code=2 comments=3 docs=0 blanks=0
+SELECT '-- not a comment', 'it''s /* fine'
+FROM t; /* block
+comment */
+-- comment
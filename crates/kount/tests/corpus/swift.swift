@@ -0,0 +1,19 @@
+// kount: lines=19 code=9 comments=10 docs=1 blanks=0
+/// Swift doc comment
+/* outer /* nested */ still outer */
+let url = "http://example.com /* not a comment"
+// comment
+let raw = #"""
+    "" " not closed by quotes alone
+    // inside the raw string
+    """#
+// comment
+let multi = """
+  /* inside a multi-line string
+  """
+// comment
+/*
+ /* nested
+ */
+ still a comment */
+let done = true
@@ -0,0 +1,241 @@
+use std::fmt::Write as _;
+use std::fs::File;
+use std::io::Write;
+
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+/// Write a `.tar.gz` archive under the test root containing `entries`.
+fn create_tar_gz(ctx: &TestContext, name: &str, entries: &[(&str, &str)]) {
+    let file = File::create(ctx.root.join(name)).unwrap();
+    let mut builder = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    for (path, content) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder
+            .append_data(&mut header, path, content.as_bytes())
+            .unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap();
+}
+
+/// Write a `.zip` archive under the test root containing `entries`.
+fn create_zip(ctx: &TestContext, name: &str, entries: &[(&str, &str)]) {
+    let file = File::create(ctx.root.join(name)).unwrap();
+    let mut zip = zip::ZipWriter::new(file);
+    for (path, content) in entries {
+        zip.start_file(*path, zip::write::SimpleFileOptions::default())
+            .unwrap();
+        zip.write_all(content.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn archives_count_tar_gz_entries() {
+    let ctx = TestContext::new();
+    create_tar_gz(
+        &ctx,
+        "release.tar.gz",
+        &[
+            ("src/main.c", "int main() {\n  return 0;\n}\n"),
+            ("README", "hi\n"),
+        ],
+    );
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--archives", "--sort", "name", "release.tar.gz"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        1  release.tar.gz!/README
+        3  release.tar.gz!/src/main.c
+    ─────  ──────────────────────────────
+        4  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn archives_count_zip_entries() {
+    let ctx = TestContext::new();
+    create_zip(
+        &ctx,
+        "drop.zip",
+        &[("lib/a.py", "a\nb\n"), ("lib/b.py", "c\n")],
+    );
+    ctx.create_file("main.py", 4);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--archives", "--summary", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 7 lines in 3 files
+
+    By extension:
+      .py           7 lines 100.0%    3 files 100.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         2.3      2      4      4      4  ./main.py
+      .py           2.3      2      4      4      4  ./main.py
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 3
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn archives_apply_filters_to_entries() {
+    let ctx = TestContext::new();
+    create_zip(&ctx, "drop.zip", &[("a.rs", "1\n2\n"), ("b.md", "1\n")]);
+    create_tar_gz(&ctx, "src.tgz", &[("c.rs", "1\n"), ("d.json", "{}\n")]);
+    ctx.create_file("e.md", 3);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--archives", "--ext", "rs", "--sort", "name", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  ./drop.zip!/a.rs
+        1  ./src.tgz!/c.rs
+    ─────  ──────────────────────────────
+        3  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn archives_honour_excluding_globs() {
+    let ctx = TestContext::new();
+    ctx.create_file("old/c.rs", 3);
+    create_zip(&ctx, "drop.zip", &[("a.rs", "1\n2\n")]);
+    create_zip(&ctx, "old/drop.zip", &[("b.rs", "1\n")]);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--archives", "--ext", "rs", "--glob", "!old/**", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  ./drop.zip!/a.rs
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn archives_are_opaque_by_default() {
+    let ctx = TestContext::new();
+    create_zip(&ctx, "drop.zip", &[("a.rs", "1\n2\n")]);
+
+    let output = ctx
+        .command()
+        .args(["--json", "drop.zip"])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["path"], "drop.zip");
+}
+
+#[test]
+fn archives_corrupt_archive_warns() {
+    let ctx = TestContext::new();
+    ctx.create_file("broken.zip", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--archives", "broken.zip"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+    ─────  ──────────────────────────────
+        0  total (0 files)
+
+    ----- stderr -----
+    warning: broken.zip: invalid Zip archive: Could not find EOCD
+    ");
+}
+
+#[test]
+fn archives_keep_entries_before_a_broken_one() {
+    let ctx = TestContext::new();
+    // Lines that don't compress well, so that truncating the archive cuts into them
+    let mut state = 1u64;
+    let mut noise = String::new();
+    for _ in 0..20_000 {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1);
+        writeln!(noise, "{state:016x}").unwrap();
+    }
+    create_tar_gz(
+        &ctx,
+        "release.tar.gz",
+        &[
+            ("a.txt", "one\ntwo\n"),
+            ("b.txt", &noise),
+            ("c.txt", "three\n"),
+        ],
+    );
+    let archive = File::options()
+        .write(true)
+        .open(ctx.root.join("release.tar.gz"))
+        .unwrap();
+    let len = archive.metadata().unwrap().len();
+    archive.set_len(len / 2).unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--archives", "release.tar.gz"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  release.tar.gz!/a.txt
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    ----- stderr -----
+    warning: release.tar.gz!/b.txt: incomplete deflate stream
+    warning: release.tar.gz: incomplete deflate stream
+    ");
+}
+
+#[test]
+fn archives_skip_entries_over_max_filesize() {
+    let ctx = TestContext::new();
+    create_zip(
+        &ctx,
+        "bundle.zip",
+        &[("small.txt", "a\nb\n"), ("big.txt", &"line\n".repeat(400))],
+    );
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--archives", "--max-filesize", "1K", "bundle.zip"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  bundle.zip!/small.txt
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    ----- stderr -----
+    ");
+}
@@ -0,0 +1,235 @@
+use std::path::Path;
+
+use assert_cmd::Command;
+use assert_fs::prelude::*;
+use regex::Regex;
+
+use crate::common::{get_bin, TestContext};
+use crate::kount_snapshot;
+
+/// Every file in `tests/corpus` states on its first line how its lines should
+/// be classified, as `kount: lines=N code=N comments=N docs=N blanks=N`.
+#[test]
+fn corpus_conformance() {
+    let corpus = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/corpus");
+    let expected =
+        Regex::new(r"kount: lines=(\d+) code=(\d+) comments=(\d+) docs=(\d+) blanks=(\d+)")
+            .unwrap();
+
+    let mut entries: Vec<_> = std::fs::read_dir(&corpus)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    entries.sort();
+    assert!(!entries.is_empty());
+
+    let mut failures = Vec::new();
+    for path in entries {
+        let content = std::fs::read_to_string(&path).unwrap();
+        let header = content.lines().next().unwrap_or_default();
+        let captures = expected
+            .captures(header)
+            .unwrap_or_else(|| panic!("{} has no expected counts", path.display()));
+        let expected: Vec<u64> = (1..=5).map(|i| captures[i].parse().unwrap()).collect();
+
+        let output = Command::new(get_bin())
+            .args(["--json", "--classify"])
+            .arg(&path)
+            .output()
+            .expect("Failed to run kount");
+        assert!(output.status.success());
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+        let file = &json["files"][0];
+        let actual: Vec<u64> = ["lines", "code", "comments", "doc_lines", "blanks"]
+            .iter()
+            .map(|field| file[field].as_u64().unwrap())
+            .collect();
+
+        if actual != expected {
+            failures.push(format!(
+                "{}: expected {expected:?}, got {actual:?} (lines, code, comments, docs, blanks)",
+                path.file_name().unwrap().to_string_lossy()
+            ));
+        }
+    }
+    assert!(failures.is_empty(), "{}", failures.join("\n"));
+}
+
+#[test]
+fn classify_adds_columns() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("main.rs")
+        .write_str("// comment\nfn main() {}\n\n/* block\n   comment */\n")
+        .unwrap();
+    ctx.root
+        .child("app.py")
+        .write_str("# comment\nprint('# not a comment')\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--classify", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  Code  Comments  Docs  Blanks  File
+    ─────  ────  ────────  ────  ──────  ──────────────────────────────
+        5     1         3     0       1  ./main.rs
+        2     1         1     0       0  ./app.py
+    ─────  ────  ────────  ────  ──────  ──────────────────────────────
+        7     2         4     0       1  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn classify_in_summary() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("main.rs")
+        .write_str("// comment\nfn main() {}\n\n")
+        .unwrap();
+    ctx.root
+        .child("notes.txt")
+        .write_str("# plain text has no comments\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--classify", "--summary", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 4 lines, 2 code, 1 comments, 0 docs, 1 blanks in 2 files
+
+    By extension:
+      .rs           3 lines  75.0%       1 code       1 comments       0 docs       1 blanks    1 files  50.0%
+      .txt          1 lines  25.0%       1 code       0 comments       0 docs       0 blanks    1 files  50.0%
+
+    Documentation (doc lines / lines):
+      (all)         0   0.0%
+      .rs           0   0.0%
+      .txt          0   0.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         2.0      1      3      3      3  ./main.rs
+      .rs           3.0      3      3      3      3  ./main.rs
+      .txt          1.0      1      1      1      1  ./notes.txt
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 2
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn doc_lines_in_summary() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("lib.rs")
+        .write_str("//! Crate docs\n/// Item docs\nfn f() {}\n// plain comment\n")
+        .unwrap();
+    ctx.root
+        .child("app.py")
+        .write_str("def f():\n    \"\"\"Docstring.\"\"\"\n    return 1\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--classify", "--summary", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 7 lines, 3 code, 4 comments, 3 docs, 0 blanks in 2 files
+
+    By extension:
+      .rs           4 lines  57.1%       1 code       3 comments       2 docs       0 blanks    1 files  50.0%
+      .py           3 lines  42.9%       2 code       1 comments       1 docs       0 blanks    1 files  50.0%
+
+    Documentation (doc lines / lines):
+      (all)         3  42.9%
+      .rs           2  50.0%
+      .py           1  33.3%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         3.5      3      4      4      4  ./lib.rs
+      .rs           4.0      4      4      4      4  ./lib.rs
+      .py           3.0      3      3      3      3  ./app.py
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 2
+
+    ----- stderr -----
+    ");
+
+    let output = ctx
+        .command()
+        .args(["--json", "--classify", "lib.rs"])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["comments"], 3);
+    assert_eq!(json["files"][0]["doc_lines"], 2);
+    assert_eq!(json["total_doc_lines"], 2);
+    assert_eq!(json["by_extension"][0]["total_doc_lines"], 2);
+}
+
+#[test]
+fn classify_stdin_with_lang() {
+    let ctx = TestContext::new();
+
+    let output = ctx
+        .command()
+        .args(["--json", "--classify", "--lang", "python", "-"])
+        .write_stdin("# comment\nx = 1\n\n")
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_code"], 1);
+    assert_eq!(json["total_comments"], 1);
+    assert_eq!(json["total_blanks"], 1);
+}
+
+#[test]
+fn without_classify_no_kinds_in_json() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+
+    let output = ctx
+        .command()
+        .args(["--json", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert!(json.get("total_code").is_none());
+    assert!(json["files"][0].get("code").is_none());
+}
+
+#[test]
+fn kinds_leave_out_an_unterminated_last_line() {
+    let ctx = TestContext::new();
+    ctx.root.child("a.rs").write_str("fn main() {}").unwrap();
+    ctx.root
+        .child("b.rs")
+        .write_str("fn main() {}\n// done")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--classify", "--sort", "name", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  Code  Comments  Docs  Blanks  File
+    ─────  ────  ────────  ────  ──────  ──────────────────────────────
+        0     0         0     0       0  ./a.rs
+        1     1         0     0       0  ./b.rs
+    ─────  ────  ────────  ────  ──────  ──────────────────────────────
+        1     1         0     0       0  total (2 files)
+
+    ----- stderr -----
+    ");
+}
@@ -93,12 +93,55 @@ fn count_summary_output() {
     Total: 3 lines in 2 files
 
     By extension:
-      .rs           3 lines    2 files
+      .rs           3 lines 100.0%    2 files 100.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         1.5      1      2      2      2  ./a.rs
+      .rs           1.5      1      2      2      2  ./a.rs
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 2
 
     ----- stderr -----
     ");
 }
 
+#[test]
+fn distribution_in_json() {
+    let ctx = TestContext::new();
+    for (i, lines) in [1, 2, 3, 4, 5, 6, 7, 8, 9, 250].into_iter().enumerate() {
+        ctx.create_file(&format!("f{i}.rs"), lines);
+    }
+    ctx.create_file("empty.md", 0);
+
+    let output = ctx
+        .command()
+        .args(["--json", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    let dist = &json["distribution"];
+    assert_eq!(dist["median"], 5);
+    assert_eq!(dist["p90"], 9);
+    assert_eq!(dist["p99"], 250);
+    assert_eq!(dist["max"], 250);
+    assert_eq!(dist["largest"], "./f9.rs");
+    assert_eq!(json["by_extension"][0]["distribution"]["mean"], 29.5);
+    assert_eq!(
+        json["histogram"],
+        serde_json::json!([
+            {"min": 0, "max": 0, "files": 1},
+            {"min": 1, "max": 9, "files": 9},
+            {"min": 10, "max": 99, "files": 0},
+            {"min": 100, "max": 999, "files": 1},
+        ])
+    );
+}
+
 #[test]
 fn count_empty_directory() {
     let ctx = TestContext::new();
@@ -177,24 +220,144 @@ fn top_truncates_json_output() {
 }
 
 #[test]
-fn top_is_noop_for_summary() {
+fn top_keeps_json_extensions() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 10);
+    ctx.create_file("b.py", 3);
+    ctx.create_file("c.md", 2);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--top", "1", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"].as_array().map(Vec::len), Some(1));
+    let extensions: Vec<_> = json["by_extension"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["extension"].as_str().unwrap())
+        .collect();
+    assert_eq!(extensions, ["rs", "py", "md"]);
+}
+
+#[test]
+fn top_limits_summary_extensions() {
     let ctx = TestContext::new();
     ctx.create_file("a.rs", 10);
     ctx.create_file("b.rs", 5);
+    ctx.create_file("c.py", 3);
+    ctx.create_file("d.md", 2);
 
     kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--top", "1", "."]), @"
     success: true
     exit_code: 0
     ----- stdout -----
-    Total: 15 lines in 2 files
+    Total: 20 lines in 4 files
 
     By extension:
-      .rs          15 lines    2 files
+      .rs          15 lines  75.0%    2 files  50.0%
+      (other)       5 lines  25.0%    2 files  50.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         5.0      3     10     10     10  ./a.rs
+      .rs           7.5      5     10     10     10  ./a.rs
+      (other)       2.5      2      3      3      3  ./c.py
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 3
+      10-99        ██████████████                           1
 
     ----- stderr -----
     ");
 }
 
+#[test]
+fn top_ext_overrides_top_in_summary() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 10);
+    ctx.create_file("c.py", 3);
+    ctx.create_file("d.md", 2);
+    ctx.create_file("e.md", 1);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--top", "1", "--top-ext", "2", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 16 lines in 4 files
+
+    By extension:
+      .rs          10 lines  62.5%    1 files  25.0%
+      .md           3 lines  18.8%    2 files  50.0%
+      (other)       3 lines  18.8%    1 files  25.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         4.0      2     10     10     10  ./a.rs
+      .rs          10.0     10     10     10     10  ./a.rs
+      .md           1.5      1      2      2      2  ./d.md
+      (other)       3.0      3      3      3      3  ./c.py
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 3
+      10-99        ██████████████                           1
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn top_ext_rolls_up_json() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 10);
+    ctx.create_file("b.py", 3);
+    ctx.create_file("c.md", 2);
+    ctx.create_file("d", 1);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--top-ext", "1", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"].as_array().map(Vec::len), Some(4));
+    let by_extension = json["by_extension"].as_array().unwrap();
+    assert_eq!(by_extension.len(), 2);
+    assert_eq!(by_extension[0]["extension"], "rs");
+    assert!(by_extension[0].get("rolled_up").is_none());
+    assert_eq!(by_extension[1]["extension"], "(other)");
+    assert_eq!(by_extension[1]["file_count"], 3);
+    assert_eq!(by_extension[1]["total_lines"], 6);
+    assert_eq!(
+        by_extension[1]["rolled_up"],
+        serde_json::json!(["py", "md", ""])
+    );
+}
+
+#[test]
+fn top_ext_larger_than_extension_count_has_no_other_row() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 10);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--top-ext", "1", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["by_extension"].as_array().map(Vec::len), Some(1));
+}
+
 #[test]
 fn top_zero_shows_no_files() {
     let ctx = TestContext::new();
@@ -233,3 +396,46 @@ fn top_larger_than_file_count_shows_all() {
     ----- stderr -----
     ");
 }
+
+#[test]
+fn percent_adds_share_column() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 3);
+    ctx.create_file("b.py", 1);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--percent", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines   Share  File
+    ─────  ──────  ──────────────────────────────
+        3   75.0%  ./a.rs
+        1   25.0%  ./b.py
+    ─────  ──────  ──────────────────────────────
+        4  100.0%  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn fractions_in_json() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 3);
+    ctx.create_file("b.rs", 3);
+    ctx.create_file("c.py", 2);
+
+    let output = ctx
+        .command()
+        .args(["--json", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["line_fraction"], 0.375);
+    assert_eq!(json["by_extension"][0]["extension"], "rs");
+    assert_eq!(json["by_extension"][0]["line_fraction"], 0.75);
+    assert_eq!(json["by_extension"][1]["file_fraction"], 1.0 / 3.0);
+}
@@ -0,0 +1,188 @@
+use std::fs::File;
+use std::io::Write;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+fn create_compressed(ctx: &TestContext, name: &str, content: &str) {
+    let file = File::create(ctx.root.join(name)).unwrap();
+    let ext = name.rsplit('.').next().unwrap();
+    match ext {
+        "gz" => {
+            let mut encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+            encoder.write_all(content.as_bytes()).unwrap();
+            encoder.finish().unwrap();
+        }
+        "zst" => {
+            let mut encoder = zstd::Encoder::new(file, 0).unwrap();
+            encoder.write_all(content.as_bytes()).unwrap();
+            encoder.finish().unwrap();
+        }
+        "bz2" => {
+            let mut encoder = bzip2::write::BzEncoder::new(file, bzip2::Compression::default());
+            encoder.write_all(content.as_bytes()).unwrap();
+            encoder.finish().unwrap();
+        }
+        "xz" => {
+            let mut encoder = liblzma::write::XzEncoder::new(file, 6);
+            encoder.write_all(content.as_bytes()).unwrap();
+            encoder.finish().unwrap();
+        }
+        _ => panic!("unsupported compression: {name}"),
+    }
+}
+
+#[test]
+fn decompress_counts_uncompressed_lines() {
+    let ctx = TestContext::new();
+    create_compressed(&ctx, "app.log.gz", "a\nb\nc\n");
+    create_compressed(&ctx, "data.csv.zst", "1\n2\n");
+    create_compressed(&ctx, "dump.sql.bz2", "x\n");
+    create_compressed(&ctx, "notes.txt.xz", "1\n2\n3\n4\n");
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--decompress", "--sort", "name", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  ./app.log.gz
+        2  ./data.csv.zst
+        1  ./dump.sql.bz2
+        4  ./notes.txt.xz
+    ─────  ──────────────────────────────
+       10  total (4 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn decompress_groups_by_inner_extension() {
+    let ctx = TestContext::new();
+    create_compressed(&ctx, "app.log.gz", "a\nb\nc\n");
+    create_compressed(&ctx, "old.log.xz", "a\n");
+    ctx.create_file("today.log", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--decompress", "--summary", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 6 lines in 3 files
+
+    By extension:
+      .log          6 lines 100.0%    3 files 100.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         2.0      2      3      3      3  ./app.log.gz
+      .log          2.0      2      3      3      3  ./app.log.gz
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 3
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn decompress_filters_by_inner_extension() {
+    let ctx = TestContext::new();
+    create_compressed(&ctx, "app.log.gz", "a\nb\nc\n");
+    create_compressed(&ctx, "data.csv.zst", "a\n");
+    ctx.create_file("today.log", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--decompress", "--ext", "log", "--sort", "name", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  ./app.log.gz
+        2  ./today.log
+    ─────  ──────────────────────────────
+        5  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn decompress_finds_generated_marker() {
+    let ctx = TestContext::new();
+    create_compressed(&ctx, "schema.sql.gz", "-- @generated by pg_dump\nx\n");
+    create_compressed(&ctx, "app.log.gz", "a\n");
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--decompress", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        1  ./app.log.gz
+    ─────  ──────────────────────────────
+        1  total (1 files)
+
+    Excluded 1 generated files (2 lines); --include-generated counts them
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn decompress_flags_file_in_json() {
+    let ctx = TestContext::new();
+    create_compressed(&ctx, "app.log.gz", "a\nb\nc\n");
+    ctx.create_file("plain.log", 1);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--decompress", "--sort", "name", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["lines"], 3);
+    assert_eq!(json["files"][0]["extension"], "log");
+    assert_eq!(json["files"][0]["decompressed"], true);
+    assert!(json["files"][1].get("decompressed").is_none());
+}
+
+#[test]
+fn compressed_files_are_opaque_by_default() {
+    let ctx = TestContext::new();
+    create_compressed(&ctx, "app.log.gz", &"line\n".repeat(100));
+
+    let output = ctx
+        .command()
+        .args(["--json", "app.log.gz"])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["extension"], "gz");
+    assert_ne!(json["files"][0]["lines"], 100);
+}
+
+#[test]
+fn decompress_corrupt_file_warns() {
+    let ctx = TestContext::new();
+    ctx.create_file("broken.gz", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--decompress", "broken.gz"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+    ─────  ──────────────────────────────
+        0  total (0 files)
+
+    ----- stderr -----
+    warning: broken.gz: invalid gzip header
+    ");
+}
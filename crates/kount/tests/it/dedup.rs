@@ -0,0 +1,157 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+#[test]
+fn dedup_skips_copies() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 10);
+    ctx.create_file("copy/a.rs", 10);
+    ctx.root
+        .child("copy/other.rs")
+        .write_str("different\ncontent\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--dedup", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+       10  ./a.rs
+        2  ./copy/other.rs
+    ─────  ──────────────────────────────
+       12  total (2 files)
+
+    Skipped 1 duplicate files (10 lines):
+      ./a.rs
+        = ./copy/a.rs
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn dedup_same_path_twice() {
+    let ctx = TestContext::new();
+    ctx.create_file("src/a.rs", 3);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--dedup", "src", "src/a.rs"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  src/a.rs
+    ─────  ──────────────────────────────
+        3  total (1 files)
+
+    Skipped 1 duplicate files (3 lines):
+      src/a.rs
+        = src/a.rs
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn without_dedup_copies_are_counted() {
+    let ctx = TestContext::new();
+    ctx.create_file("src/a.rs", 3);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["src", "src/a.rs"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  src/a.rs
+        3  src/a.rs
+    ─────  ──────────────────────────────
+        6  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn dedup_keeps_empty_files() {
+    let ctx = TestContext::new();
+    ctx.create_file("a/__init__.py", 0);
+    ctx.create_file("b/__init__.py", 0);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--dedup", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_files"], 2);
+    assert!(json.get("duplicates").is_none());
+}
+
+#[test]
+fn dedup_groups_in_json() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 4);
+    ctx.create_file("b.rs", 4);
+    ctx.create_file("c.rs", 4);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--dedup", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_files"], 1);
+    assert_eq!(json["total_lines"], 4);
+    assert_eq!(
+        json["duplicates"],
+        serde_json::json!([
+            {"lines": 4, "kept": "./a.rs", "duplicates": ["./b.rs", "./c.rs"]},
+        ])
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn dedup_hardlinks() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 5);
+    std::fs::hard_link(
+        ctx.root.child("a.rs").path(),
+        ctx.root.child("link.rs").path(),
+    )
+    .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--dedup", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 5 lines in 1 files
+
+    By extension:
+      .rs           5 lines 100.0%    1 files 100.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         5.0      5      5      5      5  ./a.rs
+      .rs           5.0      5      5      5      5  ./a.rs
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 1
+
+    Skipped 1 duplicate files (5 lines):
+      ./a.rs
+        = ./link.rs
+
+    ----- stderr -----
+    ");
+}
@@ -0,0 +1,185 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+const README: &str = "# Usage
+
+```rust
+// A comment
+fn main() {}
+```
+
+```
+untagged
+```
+
+~~~python title=\"example\"
+x = 1
+~~~
+
+```rs,ignore
+let x = 1;
+```
+";
+
+const COMPONENT: &str = r#"<template>
+  <div>
+    <template v-if="ok">{{ msg }}</template>
+  </div>
+</template>
+
+<script lang="ts">
+export default {};
+</script>
+
+<style scoped>
+.a { color: red; }
+</style>
+"#;
+
+const PAGE: &str = r#"<html>
+  <script src="app.js"></script>
+  <script>
+    console.log("hi");
+  </script>
+  <script type="application/ld+json">
+    {}
+  </script>
+</html>
+"#;
+
+fn embedded(ctx: &TestContext, args: &[&str], file: &str) -> serde_json::Value {
+    let output = ctx
+        .command()
+        .arg("--json")
+        .args(args)
+        .arg(file)
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    json["files"][0]["embedded"].clone()
+}
+
+#[test]
+fn markdown_code_blocks() {
+    let ctx = TestContext::new();
+    ctx.root.child("README.md").write_str(README).unwrap();
+
+    assert_eq!(
+        embedded(&ctx, &[], "README.md"),
+        serde_json::json!([
+            {"language": "Rust", "blocks": 2, "lines": 3},
+            {"language": "Python", "blocks": 1, "lines": 1},
+        ])
+    );
+    assert_eq!(
+        embedded(&ctx, &["--classify"], "README.md")[0],
+        serde_json::json!({
+            "language": "Rust",
+            "blocks": 2,
+            "lines": 3,
+            "code": 2,
+            "comments": 1,
+            "doc_lines": 0,
+            "blanks": 0,
+        })
+    );
+}
+
+#[test]
+fn vue_sections() {
+    let ctx = TestContext::new();
+    ctx.root.child("App.vue").write_str(COMPONENT).unwrap();
+
+    assert_eq!(
+        embedded(&ctx, &[], "App.vue"),
+        serde_json::json!([
+            {"language": "HTML", "blocks": 1, "lines": 3},
+            {"language": "TypeScript", "blocks": 1, "lines": 1},
+            {"language": "CSS", "blocks": 1, "lines": 1},
+        ])
+    );
+}
+
+#[test]
+fn html_script_and_style() {
+    let ctx = TestContext::new();
+    ctx.root.child("index.html").write_str(PAGE).unwrap();
+
+    assert_eq!(
+        embedded(&ctx, &[], "index.html"),
+        serde_json::json!([
+            {"language": "JavaScript", "blocks": 1, "lines": 1},
+            {"language": "JSON", "blocks": 1, "lines": 1},
+        ])
+    );
+}
+
+#[test]
+fn html_in_utf16() {
+    let ctx = TestContext::new();
+    let page: Vec<u8> = b"\xFF\xFE"
+        .iter()
+        .copied()
+        .chain(PAGE.encode_utf16().flat_map(u16::to_le_bytes))
+        .collect();
+    ctx.root.child("index.html").write_binary(&page).unwrap();
+
+    assert_eq!(
+        embedded(&ctx, &[], "index.html"),
+        serde_json::json!([
+            {"language": "JavaScript", "blocks": 1, "lines": 1},
+            {"language": "JSON", "blocks": 1, "lines": 1},
+        ])
+    );
+}
+
+#[test]
+fn no_embedded_in_other_files() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("main.rs")
+        .write_str("// ```rust\nfn main() {}\n// ```\n")
+        .unwrap();
+
+    assert_eq!(embedded(&ctx, &[], "main.rs"), serde_json::Value::Null);
+}
+
+#[test]
+fn embedded_in_summary() {
+    let ctx = TestContext::new();
+    ctx.root.child("README.md").write_str(README).unwrap();
+    ctx.root.child("App.vue").write_str(COMPONENT).unwrap();
+    ctx.root.child("docs/guide.md").write_str(README).unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 49 lines in 3 files
+
+    By extension:
+      .md          36 lines  73.5%    2 files  66.7%
+      .vue         13 lines  26.5%    1 files  33.3%
+
+    Embedded languages:
+      Rust              6 lines     4 blocks    2 files
+      HTML              3 lines     1 blocks    1 files
+      Python            2 lines     2 blocks    2 files
+      CSS               1 lines     1 blocks    1 files
+      TypeScript        1 lines     1 blocks    1 files
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)        16.3     18     18     18     18  ./docs/guide.md
+      .md          18.0     18     18     18     18  ./docs/guide.md
+      .vue         13.0     13     13     13     13  ./App.vue
+
+    File sizes (lines):
+      10-99        ████████████████████████████████████████ 3
+
+    ----- stderr -----
+    ");
+}
@@ -0,0 +1,150 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+fn utf16le(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+}
+
+fn utf16be(text: &str) -> Vec<u8> {
+    text.encode_utf16().flat_map(u16::to_be_bytes).collect()
+}
+
+fn utf32le(text: &str) -> Vec<u8> {
+    text.chars()
+        .flat_map(|c| u32::from(c).to_le_bytes())
+        .collect()
+}
+
+fn with_bom(bom: &[u8], content: Vec<u8>) -> Vec<u8> {
+    [bom.to_vec(), content].concat()
+}
+
+fn create_encoded_files(ctx: &TestContext) {
+    let text = "first line\nsecond line\nthird line\n";
+    ctx.root
+        .child("bom16le.rc")
+        .write_binary(&with_bom(b"\xFF\xFE", utf16le(text)))
+        .unwrap();
+    ctx.root
+        .child("plain16be.rc")
+        .write_binary(&utf16be(text))
+        .unwrap();
+    ctx.root
+        .child("bom32le.txt")
+        .write_binary(&with_bom(b"\xFF\xFE\0\0", utf32le(text)))
+        .unwrap();
+    ctx.root
+        .child("bom8.txt")
+        .write_binary(&with_bom(b"\xEF\xBB\xBF", text.as_bytes().to_vec()))
+        .unwrap();
+    ctx.root.child("plain8.txt").write_str(text).unwrap();
+}
+
+#[test]
+fn wide_encodings_count_lines() {
+    let ctx = TestContext::new();
+    create_encoded_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--sort", "name", "--wc", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  Words  Bytes  File
+    ─────  ─────  ─────  ──────────────────────────────
+        3      6     70  ./bom16le.rc
+        3      6    140  ./bom32le.txt
+        3      6     37  ./bom8.txt
+        3      6     68  ./plain16be.rc
+        3      6     34  ./plain8.txt
+    ─────  ─────  ─────  ──────────────────────────────
+       15     30    349  total (5 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn encoding_in_json() {
+    let ctx = TestContext::new();
+    create_encoded_files(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--sort", "name", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    let encodings: Vec<_> = json["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| (f["path"].as_str().unwrap(), f.get("encoding"), f.get("bom")))
+        .collect();
+    assert_eq!(
+        encodings,
+        [
+            ("./bom16le.rc", Some(&"utf-16le".into()), Some(&true.into())),
+            (
+                "./bom32le.txt",
+                Some(&"utf-32le".into()),
+                Some(&true.into())
+            ),
+            ("./bom8.txt", None, Some(&true.into())),
+            ("./plain16be.rc", Some(&"utf-16be".into()), None),
+            ("./plain8.txt", None, None),
+        ]
+    );
+}
+
+#[test]
+fn surrogate_pair_across_reads() {
+    let ctx = TestContext::new();
+    // The BOM and 32766 units fill 65534 bytes, so the emoji straddles the
+    // 64 KiB read buffer
+    let text = format!("{}\u{1F600}\n", "a".repeat(32766));
+    ctx.root
+        .child("wide.txt")
+        .write_binary(&with_bom(b"\xFF\xFE", utf16le(&text)))
+        .unwrap();
+
+    let output = ctx
+        .command()
+        .args(["--json", "--chars", "--max-line-length", "80", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_lines"], 1);
+    assert_eq!(json["total_chars"], 32768);
+    assert_eq!(json["line_length"]["max"], 32767);
+}
+
+#[test]
+fn utf16_on_stdin() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(
+        ctx.filters(),
+        ctx.command()
+            .arg("-")
+            .write_stdin(with_bom(b"\xFF\xFE", utf16le("one\ntwo\n"))),
+        @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  <stdin>
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    ----- stderr -----
+    "
+    );
+}
@@ -0,0 +1,147 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+#[test]
+fn files_from_file() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+    ctx.create_file("b.rs", 3);
+    ctx.create_file("c.rs", 4);
+    ctx.root
+        .child("list.txt")
+        .write_str("a.rs\nc.rs\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "list.txt"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        4  c.rs
+        2  a.rs
+    ─────  ──────────────────────────────
+        6  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn files_from_stdin() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+    ctx.create_file("b.rs", 3);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "-"]).write_stdin("b.rs\r\n\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  b.rs
+    ─────  ──────────────────────────────
+        3  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn files_from_null_separated() {
+    let ctx = TestContext::new();
+    ctx.create_file("a b.rs", 2);
+    ctx.create_file("c.rs", 3);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "-", "-0", "--sort", "name"]).write_stdin("a b.rs\0c.rs\0"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  a b.rs
+        3  c.rs
+    ─────  ──────────────────────────────
+        5  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn files_from_applies_filters() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+    ctx.create_file("notes.md", 3);
+    ctx.create_file("data.json", 4);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "-", "--ext", "rs", "--glob", "*.json"]).write_stdin("a.rs\nnotes.md\ndata.json\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        4  data.json
+        2  a.rs
+    ─────  ──────────────────────────────
+        6  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+#[cfg(unix)]
+fn files_from_missing_entry_warns() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "-"]).write_stdin("a.rs\nmissing.rs\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  a.rs
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    ----- stderr -----
+    warning: missing.rs: No such file or directory (os error 2)
+    ");
+}
+
+#[test]
+#[cfg(unix)]
+fn files_from_missing_list() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "missing.txt"]), @"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: missing.txt: No such file or directory (os error 2)
+    ");
+}
+
+#[test]
+fn files_from_conflicts_with_paths() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "-", "."]), @"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--files-from <FILE>' cannot be used with '[PATHS]...'
+
+    Usage: kount --files-from <FILE> [PATHS]...
+
+    For more information, try '--help'.
+    ");
+}
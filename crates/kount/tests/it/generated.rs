@@ -0,0 +1,256 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+fn create_tree(ctx: &TestContext) {
+    ctx.create_file("src/main.rs", 5);
+    ctx.create_file("Cargo.lock", 40);
+    ctx.create_file("api/service.pb.go", 30);
+    ctx.root
+        .child("src/schema.rs")
+        .write_str("// @generated by diesel\nmod schema;\n")
+        .unwrap();
+    ctx.create_file("vendor/lib/lib.rs", 100);
+    ctx.create_file("web/node_modules/left-pad/index.js", 20);
+}
+
+#[test]
+fn generated_and_vendored_excluded_by_default() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().arg("."), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        5  ./src/main.rs
+    ─────  ──────────────────────────────
+        5  total (1 files)
+
+    Excluded 3 generated files (72 lines); --include-generated counts them
+    Excluded 2 vendored files (120 lines); --include-vendored counts them
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn include_generated_and_vendored() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--include-generated", "--include-vendored", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+      100  ./vendor/lib/lib.rs
+       40  ./Cargo.lock
+       30  ./api/service.pb.go
+       20  ./web/node_modules/left-pad/index.js
+        5  ./src/main.rs
+        2  ./src/schema.rs
+    ─────  ──────────────────────────────
+      197  total (6 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn excluded_in_json() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--include-vendored", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_files"], 3);
+    assert_eq!(
+        json["excluded"],
+        serde_json::json!({
+            "generated": {"files": 3, "lines": 72},
+            "vendored": {"files": 0, "lines": 0},
+            "minified": {"files": 0, "lines": 0},
+        })
+    );
+    let vendored: Vec<_> = json["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|f| f["vendored"] == true)
+        .map(|f| f["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        vendored,
+        [
+            "./vendor/lib/lib.rs",
+            "./web/node_modules/left-pad/index.js"
+        ]
+    );
+}
+
+#[test]
+fn nothing_excluded_omits_json_field() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 3);
+
+    let output = ctx
+        .command()
+        .args(["--json", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert!(json.get("excluded").is_none());
+    assert!(json["files"][0].get("generated").is_none());
+}
+
+#[test]
+fn gitattributes_overrides_heuristics() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+    ctx.create_file("assets/data.json", 50);
+    ctx.root
+        .child(".gitattributes")
+        .write_str(
+            "assets/*.json linguist-generated\n\
+             vendor/** -linguist-vendored\n\
+             Cargo.lock linguist-generated=false\n",
+        )
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().arg("."), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+      100  ./vendor/lib/lib.rs
+       40  ./Cargo.lock
+        5  ./src/main.rs
+    ─────  ──────────────────────────────
+      145  total (3 files)
+
+    Excluded 3 generated files (82 lines); --include-generated counts them
+    Excluded 1 vendored files (20 lines); --include-vendored counts them
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn nested_gitattributes_take_precedence() {
+    let ctx = TestContext::new();
+    ctx.create_file("src/a.rs", 2);
+    ctx.create_file("src/gen/b.rs", 3);
+    ctx.root
+        .child(".gitattributes")
+        .write_str("*.rs linguist-generated\n")
+        .unwrap();
+    ctx.root
+        .child("src/.gitattributes")
+        .write_str("a.rs -linguist-generated\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().arg("."), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  ./src/a.rs
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    Excluded 1 generated files (3 lines); --include-generated counts them
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn vendor_dirs_above_the_root_are_ignored() {
+    let ctx = TestContext::new();
+    ctx.create_file("vendor/proj/src/main.rs", 3);
+    ctx.create_file("vendor/proj/third_party/lib.rs", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().arg("vendor/proj"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  vendor/proj/src/main.rs
+    ─────  ──────────────────────────────
+        3  total (1 files)
+
+    Excluded 1 vendored files (2 lines); --include-vendored counts them
+
+    ----- stderr -----
+    ");
+
+    // Without a path, the walk starts from the absolute current directory
+    kount_snapshot!(
+        ctx.filters(),
+        ctx.command().current_dir(ctx.root.child("vendor/proj").path()),
+        @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  [TEMP]/vendor/proj/src/main.rs
+    ─────  ──────────────────────────────
+        3  total (1 files)
+
+    Excluded 1 vendored files (2 lines); --include-vendored counts them
+
+    ----- stderr -----
+    "
+    );
+}
+
+#[test]
+fn generated_markers_only_in_comments() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("api.go")
+        .write_str("// Code generated by protoc-gen-go. DO NOT EDIT.\npackage api\n")
+        .unwrap();
+    ctx.root
+        .child("README.md")
+        .write_str("# Docs\n\nDo not edit files under vendor/ by hand. DO NOT EDIT them.\n")
+        .unwrap();
+    ctx.root
+        .child("lint.rs")
+        .write_str("const MARKER: &str = \"@generated\";\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--sort", "name", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  ./README.md
+        1  ./lint.rs
+    ─────  ──────────────────────────────
+        4  total (2 files)
+
+    Excluded 1 generated files (2 lines); --include-generated counts them
+
+    ----- stderr -----
+    ");
+}
@@ -0,0 +1,109 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+#[test]
+fn max_line_length_reports_offenders() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("long.rs")
+        .write_str(&format!("short\n{}\n{}\n", "x".repeat(30), "y".repeat(25)))
+        .unwrap();
+    ctx.root
+        .child("one.rs")
+        .write_str(&format!("{}\nok\nok\nok\n", "z".repeat(21)))
+        .unwrap();
+    ctx.create_file("fine.rs", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--max-line-length", "20"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        4  [TEMP]/one.rs
+        3  [TEMP]/long.rs
+        2  [TEMP]/fine.rs
+    ─────  ──────────────────────────────
+        9  total (3 files)
+
+    Line length: max 30, mean 11.0, 3 lines over 20 characters
+
+    Over  Longest  File
+       2       30  [TEMP]/long.rs
+       1       21  [TEMP]/one.rs
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn max_line_length_without_offenders() {
+    let ctx = TestContext::new();
+    ctx.root.child("a.txt").write_str("abc\nabcdef\n").unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--max-line-length", "80"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 2 lines in 1 files
+
+    By extension:
+      .txt          2 lines 100.0%    1 files 100.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         2.0      2      2      2      2  [TEMP]/a.txt
+      .txt          2.0      2      2      2      2  [TEMP]/a.txt
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 1
+
+    Line length: max 6, mean 4.5, 0 lines over 80 characters
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn line_length_counts_characters_without_terminator() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("a.txt")
+        .write_str("héllo\r\n\nwörld!")
+        .unwrap();
+
+    let output = ctx
+        .command()
+        .args(["--json", "--max-line-length", "5"])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    let lengths = &json["files"][0]["line_length"];
+    assert_eq!(lengths["max"], 6);
+    assert_eq!(lengths["mean"], 11.0 / 3.0);
+    assert_eq!(lengths["limit"], 5);
+    assert_eq!(lengths["over_limit"], 1);
+    assert_eq!(json["line_length"]["over_limit"], 1);
+}
+
+#[test]
+fn line_length_omitted_from_json_by_default() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+
+    let output = ctx
+        .command()
+        .arg("--json")
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert!(json["files"][0].get("line_length").is_none());
+    assert!(json.get("line_length").is_none());
+}
@@ -0,0 +1,119 @@
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+fn create_files(ctx: &TestContext) {
+    ctx.create_file("stub.rs", 1);
+    ctx.create_file("small.rs", 5);
+    ctx.create_file("medium.py", 40);
+    ctx.create_file("huge.rs", 300);
+}
+
+#[test]
+fn min_and_max_lines() {
+    let ctx = TestContext::new();
+    create_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--min-lines", "2", "--max-lines", "100", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+       40  ./medium.py
+        5  ./small.rs
+    ─────  ──────────────────────────────
+       45  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn max_lines_finds_stubs() {
+    let ctx = TestContext::new();
+    create_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--max-lines", "1", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        1  ./stub.rs
+    ─────  ──────────────────────────────
+        1  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn totals_all_keeps_full_totals() {
+    let ctx = TestContext::new();
+    create_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--min-lines", "100", "--totals", "all", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+      300  ./huge.rs
+    ─────  ──────────────────────────────
+      346  total (4 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn line_range_in_summary() {
+    let ctx = TestContext::new();
+    create_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--min-lines", "5", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 345 lines in 3 files
+
+    By extension:
+      .rs         305 lines  88.4%    2 files  66.7%
+      .py          40 lines  11.6%    1 files  33.3%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)       115.0     40    300    300    300  ./huge.rs
+      .rs         152.5      5    300    300    300  ./huge.rs
+      .py          40.0     40     40     40     40  ./medium.py
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 1
+      10-99        ████████████████████████████████████████ 1
+      100-999      ████████████████████████████████████████ 1
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn line_range_in_json() {
+    let ctx = TestContext::new();
+    create_files(&ctx);
+
+    for (totals, total_lines, total_files) in [("filtered", 45, 2), ("all", 346, 4)] {
+        let output = ctx
+            .command()
+            .args(["--json", "--min-lines", "5", "--max-lines", "40"])
+            .args(["--totals", totals, "."])
+            .output()
+            .expect("Failed to run kount");
+
+        assert!(output.status.success());
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+        assert_eq!(json["files"].as_array().map(Vec::len), Some(2));
+        assert_eq!(json["total_lines"], total_lines);
+        assert_eq!(json["total_files"], total_files);
+    }
+}
@@ -1,4 +1,24 @@
+mod archives;
+mod classify;
 mod common;
 mod count;
+mod decompress;
+mod dedup;
+mod embedded;
+mod encoding;
+mod files_from;
+mod generated;
 mod help;
+mod line_length;
+mod line_range;
+mod markers;
+mod metadata_filters;
+mod minified;
+mod notebook;
+mod stdin;
+mod test_code;
+mod tui;
 mod version;
+mod walk;
+mod watch;
+mod wc;
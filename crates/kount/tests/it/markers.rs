@@ -0,0 +1,244 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+fn create_tree(ctx: &TestContext) {
+    ctx.root
+        .child("main.rs")
+        .write_str(
+            "// TODO: split this up\n\
+             fn main() {\n    \
+                 let s = \"TODO: not a comment\";\n    \
+                 /* FIXME(alice) and TODO */\n\
+             }\n\
+             // TODOS and XXXL are other words\n",
+        )
+        .unwrap();
+    ctx.root
+        .child("app.py")
+        .write_str("def f():\n    \"\"\"HACK: docstrings count.\"\"\"\n    return 1  # XXX\n")
+        .unwrap();
+    ctx.root
+        .child("notes.txt")
+        .write_str("TODO: plain text has no comments\n")
+        .unwrap();
+}
+
+#[test]
+fn markers_listed_per_file() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--markers", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        6  ./main.rs
+        3  ./app.py
+        1  ./notes.txt
+    ─────  ──────────────────────────────
+       10  total (3 files)
+
+    Markers: 1 FIXME, 1 HACK, 2 TODO, 1 XXX
+      FIXME  HACK  TODO  XXX  File
+          1     0     2    0  ./main.rs
+          0     1     0    1  ./app.py
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn marker_locations() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--markers=TODO,FIXME", "--marker-locations", "main.rs"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        6  main.rs
+    ─────  ──────────────────────────────
+        6  total (1 files)
+
+    Markers: 1 FIXME, 2 TODO
+      FIXME  TODO  File
+          1     2  main.rs
+
+    main.rs:1: TODO
+    main.rs:4: FIXME
+    main.rs:4: TODO
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn markers_per_extension_in_summary() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--markers", "--summary", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 10 lines in 3 files
+
+    By extension:
+      .rs           6 lines  60.0%    1 files  33.3%
+      .py           3 lines  30.0%    1 files  33.3%
+      .txt          1 lines  10.0%    1 files  33.3%
+
+    Markers: 1 FIXME, 1 HACK, 2 TODO, 1 XXX
+      FIXME  HACK  TODO  XXX  Extension
+          1     0     2    0  .rs
+          0     1     0    1  .py
+          0     0     0    0  .txt
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         3.3      3      6      6      6  ./main.rs
+      .rs           6.0      6      6      6      6  ./main.rs
+      .py           3.0      3      3      3      3  ./app.py
+      .txt          1.0      1      1      1      1  ./notes.txt
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 3
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn markers_in_json() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--markers", "--marker-locations", "."])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(
+        json["markers"],
+        serde_json::json!({"FIXME": 1, "HACK": 1, "TODO": 2, "XXX": 1})
+    );
+    let rust = &json["by_extension"][0];
+    assert_eq!(rust["extension"], "rs");
+    assert_eq!(
+        rust["markers"],
+        serde_json::json!({"FIXME": 1, "HACK": 0, "TODO": 2, "XXX": 0})
+    );
+    let main = &json["files"][0];
+    assert_eq!(main["path"], "./main.rs");
+    assert_eq!(
+        main["marker_locations"],
+        serde_json::json!([
+            {"marker": "TODO", "line": 1},
+            {"marker": "FIXME", "line": 4},
+            {"marker": "TODO", "line": 4},
+        ])
+    );
+}
+
+#[test]
+fn no_markers_by_default() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "."])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert!(json.get("markers").is_none());
+    assert!(json["files"][0].get("markers").is_none());
+}
+
+#[test]
+fn marker_locations_requires_markers() {
+    let ctx = TestContext::new();
+
+    let output = ctx
+        .command()
+        .args(["--marker-locations", "."])
+        .output()
+        .expect("Failed to run kount");
+    assert!(!output.status.success());
+}
+
+#[test]
+fn markers_in_markdown_code_blocks() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("README.md")
+        .write_str("# TODO: not a comment\n\n```rust\n// TODO x\nfn main() {}\n```\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--markers", "--marker-locations", "README.md"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        6  README.md
+    ─────  ──────────────────────────────
+        6  total (1 files)
+
+    Markers: 0 FIXME, 0 HACK, 1 TODO, 0 XXX
+      FIXME  HACK  TODO  XXX  File
+          0     0     1    0  README.md
+
+    README.md:4: TODO
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn markers_in_notebook_cells() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("analysis.ipynb")
+        .write_str(
+            r##"{
+ "cells": [
+  {"cell_type": "markdown", "metadata": {}, "source": ["# TODO: a heading\n", "text"]},
+  {"cell_type": "code", "metadata": {}, "outputs": [], "source": ["x = 1\n", "# TODO fix\n"]}
+ ],
+ "metadata": {"kernelspec": {"language": "python", "name": "python3"}},
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"##,
+        )
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--markers", "--marker-locations", "analysis.ipynb"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        4  analysis.ipynb
+    ─────  ──────────────────────────────
+        4  total (1 files)
+
+    Markers: 0 FIXME, 0 HACK, 1 TODO, 0 XXX
+      FIXME  HACK  TODO  XXX  File
+          0     0     1    0  analysis.ipynb
+
+    analysis.ipynb:cell 2:2: TODO
+
+    ----- stderr -----
+    ");
+}
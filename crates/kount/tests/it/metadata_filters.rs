@@ -0,0 +1,176 @@
+use std::fs::File;
+use std::time::{Duration, SystemTime};
+
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+const DAY: Duration = Duration::from_hours(24);
+
+fn set_age(ctx: &TestContext, name: &str, age: Duration) {
+    let file = File::options()
+        .write(true)
+        .open(ctx.root.child(name).path())
+        .unwrap();
+    file.set_modified(SystemTime::now() - age).unwrap();
+}
+
+fn create_aged_files(ctx: &TestContext) {
+    ctx.create_file("fresh.rs", 3);
+    ctx.create_file("month.rs", 2);
+    ctx.create_file("stale.rs", 1);
+    set_age(ctx, "month.rs", 20 * DAY);
+    set_age(ctx, "stale.rs", 400 * DAY);
+}
+
+#[test]
+fn newer_than_duration() {
+    let ctx = TestContext::new();
+    create_aged_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--newer-than", "30d", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  ./fresh.rs
+        2  ./month.rs
+    ─────  ──────────────────────────────
+        5  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn older_than_duration() {
+    let ctx = TestContext::new();
+    create_aged_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--older-than", "1 week", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  ./month.rs
+        1  ./stale.rs
+    ─────  ──────────────────────────────
+        3  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn newer_and_older_than_combine() {
+    let ctx = TestContext::new();
+    create_aged_files(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--newer-than", "P1Y", "--older-than", "2w", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  ./month.rs
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn older_than_date() {
+    let ctx = TestContext::new();
+    create_aged_files(&ctx);
+    set_age(&ctx, "stale.rs", 365 * 30 * DAY);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--older-than", "2000-01-01", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        1  ./stale.rs
+    ─────  ──────────────────────────────
+        1  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn max_filesize_skips_large_files() {
+    let ctx = TestContext::new();
+    ctx.create_file("small.rs", 2);
+    ctx.root
+        .child("fixture.json")
+        .write_str(&"{}\n".repeat(1024))
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--max-filesize", "1K", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  ./small.rs
+    ─────  ──────────────────────────────
+        2  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn metadata_filters_apply_to_files_from() {
+    let ctx = TestContext::new();
+    create_aged_files(&ctx);
+    ctx.root
+        .child("list.txt")
+        .write_str("fresh.rs\nstale.rs\n")
+        .unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--files-from", "list.txt", "--newer-than", "30d"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  fresh.rs
+    ─────  ──────────────────────────────
+        3  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn invalid_filter_values() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--max-filesize", "10X"]), @"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value '10X' for '--max-filesize <SIZE>': invalid size `10X`, expected e.g. 512K, 10M or 2G
+
+    For more information, try '--help'.
+    ");
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--newer-than", "last week"]), @"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'last week' for '--newer-than <WHEN>': invalid time `last week`, expected a duration like 30d or a date like 2024-06-01
+
+    For more information, try '--help'.
+    ");
+}
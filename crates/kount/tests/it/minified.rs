@@ -0,0 +1,88 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+fn create_tree(ctx: &TestContext) {
+    ctx.create_file("src/app.js", 12);
+    ctx.root
+        .child("dist/bundle.js")
+        .write_str(&format!("{}\n", "function f(a){return a+1};".repeat(100)))
+        .unwrap();
+    ctx.root
+        .child("dist/theme.min.css")
+        .write_str("body{margin:0}\n")
+        .unwrap();
+    // Long lines of prose are not minified
+    ctx.root
+        .child("docs/notes.md")
+        .write_str(&format!("{}\n", "a long line of prose ".repeat(50)))
+        .unwrap();
+}
+
+#[test]
+fn minified_flagged_in_json() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--sort", "name", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_files"], 4);
+    let minified: Vec<_> = json["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|f| f["minified"] == true)
+        .map(|f| f["path"].as_str().unwrap())
+        .collect();
+    assert_eq!(minified, ["./dist/bundle.js", "./dist/theme.min.css"]);
+}
+
+#[test]
+fn skip_minified() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--skip-minified", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+       12  ./src/app.js
+        1  ./docs/notes.md
+    ─────  ──────────────────────────────
+       13  total (2 files)
+
+    Excluded 2 minified files (2 lines)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn skip_minified_in_json() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--skip-minified", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_lines"], 13);
+    assert_eq!(
+        json["excluded"]["minified"],
+        serde_json::json!({"files": 2, "lines": 2})
+    );
+}
@@ -0,0 +1,155 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+const NOTEBOOK: &str = r##"{
+ "cells": [
+  {
+   "cell_type": "markdown",
+   "metadata": {},
+   "source": ["# Analysis\n", "\n", "Load the data."]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": 1,
+   "metadata": {},
+   "outputs": [
+    {
+     "name": "stdout",
+     "output_type": "stream",
+     "text": ["1\n", "2\n", "3\n"]
+    }
+   ],
+   "source": ["# load\n", "import pandas as pd\n", "df = pd.read_csv(\"data.csv\")"]
+  },
+  {
+   "cell_type": "code",
+   "execution_count": null,
+   "metadata": {},
+   "outputs": [],
+   "source": "df.head()"
+  },
+  {
+   "cell_type": "raw",
+   "metadata": {},
+   "source": ["raw text"]
+  }
+ ],
+ "metadata": {
+  "kernelspec": {
+   "display_name": "Python 3",
+   "language": "python",
+   "name": "python3"
+  }
+ },
+ "nbformat": 4,
+ "nbformat_minor": 5
+}
+"##;
+
+fn create_notebook(ctx: &TestContext) {
+    ctx.root
+        .child("analysis.ipynb")
+        .write_str(NOTEBOOK)
+        .unwrap();
+}
+
+#[test]
+fn notebook_counts_cell_sources() {
+    let ctx = TestContext::new();
+    create_notebook(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().arg("."), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        7  ./analysis.ipynb
+    ─────  ──────────────────────────────
+        7  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn notebook_cells_by_language() {
+    let ctx = TestContext::new();
+    create_notebook(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--classify", "."])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    let file = &json["files"][0];
+    assert_eq!(file["lines"], 7);
+    assert_eq!(file["code"], 5);
+    assert_eq!(file["comments"], 1);
+    assert_eq!(file["blanks"], 1);
+    assert_eq!(
+        file["embedded"],
+        serde_json::json!([
+            {
+                "language": "Python",
+                "blocks": 2,
+                "lines": 4,
+                "code": 3,
+                "comments": 1,
+                "doc_lines": 0,
+                "blanks": 0,
+            },
+            {
+                "language": "Markdown",
+                "blocks": 1,
+                "lines": 3,
+                "code": 2,
+                "comments": 0,
+                "doc_lines": 0,
+                "blanks": 1,
+            },
+        ])
+    );
+    assert_eq!(json["by_extension"][0]["extension"], "ipynb");
+    assert_eq!(json["by_extension"][0]["total_lines"], 7);
+}
+
+#[test]
+fn notebook_bytes_are_the_file_size() {
+    let ctx = TestContext::new();
+    create_notebook(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--wc", "."])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    let file = &json["files"][0];
+    assert_eq!(file["bytes"], NOTEBOOK.len());
+    assert_eq!(file["lines"], 7);
+}
+
+#[test]
+fn invalid_notebook_counted_as_json() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("broken.ipynb")
+        .write_str("{\n  \"cells\": \n")
+        .unwrap();
+
+    let output = ctx
+        .command()
+        .args(["--json", "."])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["lines"], 2);
+    assert!(json["files"][0].get("embedded").is_none());
+}
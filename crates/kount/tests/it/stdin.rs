@@ -0,0 +1,149 @@
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+#[test]
+fn stdin_dash_counts_piped_content() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().arg("-").write_stdin("a\nb\nc\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  <stdin>
+    ─────  ──────────────────────────────
+        3  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn stdin_name_sets_path_and_extension() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--stdin-name", "foo.rs", "-"]).write_stdin("fn main() {}\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 1 lines in 1 files
+
+    By extension:
+      .rs           1 lines 100.0%    1 files 100.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         1.0      1      1      1      1  foo.rs
+      .rs           1.0      1      1      1      1  foo.rs
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 1
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn stdin_lang_groups_by_language_extension() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--lang", "rust", "-", "a.rs"]).write_stdin("fn main() {}\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 3 lines in 2 files
+
+    By extension:
+      .rs           3 lines 100.0%    2 files 100.0%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         1.5      1      2      2      2  a.rs
+      .rs           1.5      1      2      2      2  a.rs
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 2
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn stdin_json_reports_extension() {
+    let ctx = TestContext::new();
+
+    let output = ctx
+        .command()
+        .args(["--json", "--lang", "Python", "-"])
+        .write_stdin("print(1)\nprint(2)\n")
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["path"], "<stdin>");
+    assert_eq!(json["files"][0]["extension"], "py");
+    assert_eq!(json["total_lines"], 2);
+}
+
+#[test]
+fn stdin_unknown_language() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--lang", "klingon", "-"]), @"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: invalid value 'klingon' for '--lang <LANG>': unknown language `klingon`
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn stdin_generated_is_excluded() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--stdin-name", "schema.rs", "-"]).write_stdin("// @generated by diesel\nmod schema;\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+    ─────  ──────────────────────────────
+        0  total (0 files)
+
+    Excluded 1 generated files (2 lines); --include-generated counts them
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn stdin_dedup_against_files() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.txt", 3);
+    let content = ctx.read_file("a.txt");
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--dedup", "a.txt", "-"]).write_stdin(content), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        3  <stdin>
+    ─────  ──────────────────────────────
+        3  total (1 files)
+
+    Skipped 1 duplicate files (3 lines):
+      <stdin>
+        = a.txt
+
+    ----- stderr -----
+    ");
+}
@@ -0,0 +1,175 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+const LIB_RS: &str = r#"pub fn add(a: u32, b: u32) -> u32 {
+    a + b
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn adds() {
+        // a } in a comment
+        assert_eq!(format!("{}}}", add(1, 2)), "3}");
+        assert_eq!('{', '{');
+    }
+}
+"#;
+
+fn create_tree(ctx: &TestContext) {
+    ctx.create_file("src/server.go", 20);
+    ctx.create_file("src/server_test.go", 10);
+    ctx.create_file("app/test_parser.py", 8);
+    ctx.create_file("web/app.spec.ts", 6);
+    ctx.create_file("tests/cli.rs", 4);
+    ctx.root.child("src/lib.rs").write_str(LIB_RS).unwrap();
+}
+
+#[test]
+fn test_lines_in_json() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--sort", "name", "."])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+
+    let test_lines: Vec<(&str, u64)> = json["files"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| {
+            (
+                f["path"].as_str().unwrap(),
+                f["test_lines"].as_u64().unwrap_or(0),
+            )
+        })
+        .collect();
+    assert_eq!(
+        test_lines,
+        [
+            ("./app/test_parser.py", 8),
+            ("./src/lib.rs", 11),
+            ("./src/server.go", 0),
+            ("./src/server_test.go", 10),
+            ("./tests/cli.rs", 4),
+            ("./web/app.spec.ts", 6),
+        ]
+    );
+    assert_eq!(json["test_lines"], 39);
+    assert_eq!(json["non_test_lines"], 24);
+}
+
+#[test]
+fn test_ratio_in_summary() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "--top-ext", "1", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 63 lines in 6 files
+    Tests: 39 test lines, 24 non-test lines (61.9% test code, ratio 1.62)
+
+    By extension:
+      .go          30 lines  47.6%    2 files  33.3%
+      (other)      33 lines  52.4%    4 files  66.7%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)        10.5      8     20     20     20  ./src/server.go
+      .go          15.0     10     20     20     20  ./src/server.go
+      (other)       8.2      6     15     15     15  ./src/lib.rs
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 3
+      10-99        ████████████████████████████████████████ 3
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn no_test_ratio_without_tests() {
+    let ctx = TestContext::new();
+    ctx.create_file("src/main.rs", 3);
+
+    let output = ctx
+        .command()
+        .args(["--summary", "."])
+        .output()
+        .expect("Failed to run kount");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("Tests:"));
+}
+
+#[test]
+fn test_module_braces_follow_the_lexer() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("src/lib.rs")
+        .write_str(
+            r##"pub fn answer() -> u32 {
+    42
+}
+
+#[cfg(test)]
+// The module below is test code
+mod tests {
+    /* } */
+    const OPEN: &str = "}";
+    const RAW: &str = r#"}"#;
+
+    #[test]
+    fn answers() {
+        assert_eq!(super::answer(), 42);
+    }
+}
+
+pub fn after() {}
+"##,
+        )
+        .unwrap();
+
+    let output = ctx
+        .command()
+        .args(["--json", "src/lib.rs"])
+        .output()
+        .expect("Failed to run kount");
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["test_lines"], 12);
+}
+
+#[test]
+fn test_dirs_above_the_root_are_ignored() {
+    let ctx = TestContext::new();
+    ctx.create_file("test/proj/src/main.rs", 3);
+    ctx.create_file("test/proj/tests/cli.rs", 2);
+
+    let test_lines = |command: &mut assert_cmd::Command| {
+        let output = command.output().expect("Failed to run kount");
+        let json: serde_json::Value =
+            serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+        json["test_lines"].as_u64().unwrap()
+    };
+
+    assert_eq!(test_lines(ctx.command().args(["--json", "test/proj"])), 2);
+    assert_eq!(
+        test_lines(
+            ctx.command()
+                .arg("--json")
+                .current_dir(ctx.root.child("test/proj").path())
+        ),
+        2
+    );
+}
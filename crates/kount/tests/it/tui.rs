@@ -0,0 +1,70 @@
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+#[test]
+fn tui_help_output() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["tui", "--help"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Explore the counts in an interactive terminal interface
+
+    Usage: kount tui [OPTIONS] [PATHS]...
+
+    Arguments:
+      [PATHS]...  Files or directories to count, `-` for stdin (default: current directory)
+
+    Options:
+      -e, --ext <EXT>            Filter by extension (comma-separated: rs,py,js)
+      -g, --glob <GLOB>          Filter by glob pattern (repeatable)
+      -s, --sort <SORT>          Sort order [default: lines] [default: lines] [possible values: lines, name, none]
+          --no-ignore            Include hidden files, ignore .gitignore
+          --files-from <FILE>    Read the files to count from FILE instead of walking (`-` for stdin)
+      -0, --null                 Paths in --files-from are separated by NUL instead of newlines
+          --stdin-name <NAME>    File name to report for content read from stdin
+          --lang <LANG>          Language of content read from stdin (e.g. rust, python, js)
+          --archives             Count the files inside tar, tar.gz and zip archives
+          --decompress           Count the uncompressed lines of .gz, .zst, .bz2 and .xz files
+          --min-lines <N>        Only show files with at least N lines
+          --max-lines <N>        Only show files with at most N lines
+          --totals <TOTALS>      Compute totals over the files in the line range or all counted files [default: filtered] [possible values: filtered, all]
+          --max-filesize <SIZE>  Skip files larger than SIZE without reading them (e.g. 512K, 10M, 2G)
+          --newer-than <WHEN>    Only count files modified within DURATION or since DATE (e.g. 30d, 2024-06-01)
+          --older-than <WHEN>    Only count files last modified more than DURATION ago or before DATE
+          --max-depth <N>        Descend at most N directories below each path
+      -L, --follow-symlinks      Follow symbolic links, reporting links that loop back
+          --one-file-system      Don't cross into other file systems, such as mounted volumes
+          --dedup                Count identical files and hardlinks once, listing the duplicates
+          --include-generated    Count generated files, such as lockfiles and protobuf stubs
+          --include-vendored     Count third-party code in directories such as vendor/
+          --skip-minified        Leave out minified files, such as bundled JavaScript and CSS
+      -c, --bytes                Also count bytes
+      -m, --chars                Also count UTF-8 characters
+      -w, --words                Also count whitespace-separated words
+          --wc                   Count lines, words and bytes, like `wc`
+          --max-line-length <N>  Report line lengths and the files with lines longer than N characters
+          --classify             Split lines into code, comments, doc comments and blanks
+          --markers[=<WORDS>]    Count TODO, FIXME, HACK and XXX in comments, or the comma-separated WORDS
+          --marker-locations     List the line of each marker counted by --markers
+      -h, --help                 Print help
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn tui_requires_terminal() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 1);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["tui", "."]), @"
+    success: false
+    exit_code: 1
+    ----- stdout -----
+
+    ----- stderr -----
+    error: `kount tui` requires an interactive terminal
+    ");
+}
@@ -0,0 +1,146 @@
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+fn create_tree(ctx: &TestContext) {
+    ctx.create_file("top.rs", 1);
+    ctx.create_file("a/one.rs", 2);
+    ctx.create_file("a/b/two.rs", 3);
+}
+
+#[test]
+fn max_depth_one_counts_direct_children() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--max-depth", "1", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        1  ./top.rs
+    ─────  ──────────────────────────────
+        1  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn max_depth_two() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--max-depth", "2", "."]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  File
+    ─────  ──────────────────────────────
+        2  ./a/one.rs
+        1  ./top.rs
+    ─────  ──────────────────────────────
+        3  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn one_file_system_counts_local_tree() {
+    let ctx = TestContext::new();
+    create_tree(&ctx);
+
+    let output = ctx
+        .command()
+        .args(["--json", "--one-file-system", "."])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["total_files"], 3);
+}
+
+#[cfg(unix)]
+mod symlinks {
+    use std::os::unix::fs::symlink;
+
+    use assert_fs::prelude::*;
+
+    use super::create_tree;
+    use crate::common::TestContext;
+    use crate::kount_snapshot;
+
+    #[test]
+    fn symlinks_not_followed_by_default() {
+        let ctx = TestContext::new();
+        create_tree(&ctx);
+        symlink("../top.rs", ctx.root.child("a/link.rs").path()).unwrap();
+        symlink("a/b", ctx.root.child("shortcut").path()).unwrap();
+
+        kount_snapshot!(ctx.filters(), ctx.command().arg("."), @"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        Lines  File
+        ─────  ──────────────────────────────
+            3  ./a/b/two.rs
+            2  ./a/one.rs
+            1  ./top.rs
+        ─────  ──────────────────────────────
+            6  total (3 files)
+
+        ----- stderr -----
+        ");
+    }
+
+    #[test]
+    fn follow_symlinks() {
+        let ctx = TestContext::new();
+        create_tree(&ctx);
+        symlink("../top.rs", ctx.root.child("a/link.rs").path()).unwrap();
+        symlink("a/b", ctx.root.child("shortcut").path()).unwrap();
+
+        kount_snapshot!(ctx.filters(), ctx.command().args(["--follow-symlinks", "--sort", "name", "."]), @"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        Lines  File
+        ─────  ──────────────────────────────
+            3  ./a/b/two.rs
+            1  ./a/link.rs
+            2  ./a/one.rs
+            3  ./shortcut/two.rs
+            1  ./top.rs
+        ─────  ──────────────────────────────
+           10  total (5 files)
+
+        ----- stderr -----
+        ");
+    }
+
+    #[test]
+    fn symlink_loop_is_reported() {
+        let ctx = TestContext::new();
+        create_tree(&ctx);
+        symlink("..", ctx.root.child("a/b/up").path()).unwrap();
+
+        kount_snapshot!(ctx.filters(), ctx.command().args(["-L", "--sort", "name", "."]), @"
+        success: true
+        exit_code: 0
+        ----- stdout -----
+        Lines  File
+        ─────  ──────────────────────────────
+            3  ./a/b/two.rs
+            2  ./a/one.rs
+            1  ./top.rs
+        ─────  ──────────────────────────────
+            6  total (3 files)
+
+        ----- stderr -----
+        warning: File system loop found: ./a/b/up points to an ancestor ./a
+        ");
+    }
+}
@@ -0,0 +1,177 @@
+use std::io::Read;
+use std::process::{Child, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+/// A running `kount --watch` process whose stdout is collected in the background.
+struct WatchProcess {
+    child: Child,
+    stdout: Arc<Mutex<String>>,
+}
+
+impl WatchProcess {
+    fn spawn(ctx: &TestContext, args: &[&str]) -> Self {
+        let mut child = std::process::Command::new(crate::common::get_bin())
+            .arg("--watch")
+            .args(args)
+            .current_dir(ctx.root.path())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .expect("Failed to spawn kount --watch");
+
+        let stdout = Arc::new(Mutex::new(String::new()));
+        let mut pipe = child.stdout.take().expect("stdout is piped");
+        let buffer = Arc::clone(&stdout);
+        std::thread::spawn(move || {
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = pipe.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                buffer
+                    .lock()
+                    .unwrap()
+                    .push_str(&String::from_utf8_lossy(&chunk[..n]));
+            }
+        });
+
+        Self { child, stdout }
+    }
+
+    /// Wait until the most recent frame contains `needle`, returning that frame.
+    fn wait_for(&self, needle: &str) -> String {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let frame = {
+                let stdout = self.stdout.lock().unwrap();
+                stdout
+                    .rsplit("\x1b[2J\x1b[H")
+                    .next()
+                    .unwrap_or("")
+                    .to_string()
+            };
+            if frame.contains(needle) {
+                return frame;
+            }
+            assert!(
+                Instant::now() < deadline,
+                "timed out waiting for {needle:?}, last frame:\n{frame}"
+            );
+            std::thread::sleep(Duration::from_millis(50));
+        }
+    }
+}
+
+impl Drop for WatchProcess {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+#[test]
+#[cfg(unix)]
+fn watch_recounts_changed_files() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+
+    let watch = WatchProcess::spawn(&ctx, &["."]);
+    watch.wait_for("Since start: +0 lines, +0 files");
+
+    ctx.create_file("a.rs", 5);
+    let frame = watch.wait_for("Since start: +3 lines, +0 files");
+    assert!(frame.contains("5  ./a.rs"), "{frame}");
+
+    ctx.create_file("b.rs", 1);
+    let frame = watch.wait_for("Since start: +4 lines, +1 files");
+    assert!(frame.contains("6  total (2 files)"), "{frame}");
+}
+
+#[test]
+#[cfg(unix)]
+fn watch_applies_filters_to_events() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+
+    let watch = WatchProcess::spawn(&ctx, &["--ext", "rs", "."]);
+    watch.wait_for("Since start: +0 lines, +0 files");
+
+    ctx.create_file("notes.md", 10);
+    ctx.root
+        .child("a.rs")
+        .write_str("one\ntwo\nthree\n")
+        .unwrap();
+    let frame = watch.wait_for("Since start: +1 lines, +0 files");
+    assert!(!frame.contains("notes.md"), "{frame}");
+}
+
+#[test]
+#[cfg(unix)]
+fn watch_drops_deleted_files() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+    ctx.create_file("b.rs", 3);
+
+    let watch = WatchProcess::spawn(&ctx, &["--summary", "."]);
+    watch.wait_for("Since start: +0 lines, +0 files");
+
+    std::fs::remove_file(ctx.root.join("b.rs")).unwrap();
+    let frame = watch.wait_for("Since start: -3 lines, -1 files");
+    assert!(frame.contains("Total: 2 lines in 1 files"), "{frame}");
+}
+
+#[test]
+fn watch_conflicts_with_stdin() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--watch", "-"]), @"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--watch' cannot be used with reading from stdin ('-')
+
+    Usage: kount [OPTIONS] [PATHS]...
+           kount <COMMAND>
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn watch_conflicts_with_files_from() {
+    let ctx = TestContext::new();
+    ctx.root.child("list.txt").write_str("a.txt\n").unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--watch", "--files-from", "-"]), @"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--watch' cannot be used with '--files-from <FILE>'
+
+    Usage: kount --watch [PATHS]...
+
+    For more information, try '--help'.
+    ");
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--watch", "--files-from", "list.txt"]), @"
+    success: false
+    exit_code: 2
+    ----- stdout -----
+
+    ----- stderr -----
+    error: the argument '--watch' cannot be used with '--files-from <FILE>'
+
+    Usage: kount --watch [PATHS]...
+
+    For more information, try '--help'.
+    ");
+}
@@ -0,0 +1,140 @@
+use assert_fs::prelude::*;
+
+use crate::common::TestContext;
+use crate::kount_snapshot;
+
+#[test]
+fn wc_adds_words_and_bytes_columns() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("a.txt")
+        .write_str("one two\n  three\tfour\n\nfive\n")
+        .unwrap();
+    ctx.create_file("b.rs", 3);
+
+    kount_snapshot!(ctx.filters(), ctx.command().arg("--wc"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  Words  Bytes  File
+    ─────  ─────  ─────  ──────────────────────────────
+        4      5     27  [TEMP]/a.txt
+        3      6     21  [TEMP]/b.rs
+    ─────  ─────  ─────  ──────────────────────────────
+        7     11     48  total (2 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn chars_counts_utf8_characters() {
+    let ctx = TestContext::new();
+    ctx.root.child("a.txt").write_str("héllo wörld\n").unwrap();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--chars", "--bytes"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  Chars  Bytes  File
+    ─────  ─────  ─────  ──────────────────────────────
+        1     12     14  [TEMP]/a.txt
+    ─────  ─────  ─────  ──────────────────────────────
+        1     12     14  total (1 files)
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn words_in_summary() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("a.txt")
+        .write_str("one two\nthree\n")
+        .unwrap();
+    ctx.root.child("b.txt").write_str("four\n").unwrap();
+    ctx.create_file("c.rs", 2);
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--summary", "-w"]), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Total: 5 lines, 8 words in 3 files
+
+    By extension:
+      .txt          3 lines  60.0%       4 words    2 files  66.7%
+      .rs           2 lines  40.0%       4 words    1 files  33.3%
+
+    Lines per file:
+                   Mean Median    P90    P99    Max  Largest
+      (all)         1.7      2      2      2      2  [TEMP]/a.txt
+      .txt          1.5      1      2      2      2  [TEMP]/a.txt
+      .rs           2.0      2      2      2      2  [TEMP]/c.rs
+
+    File sizes (lines):
+      1-9          ████████████████████████████████████████ 3
+
+    ----- stderr -----
+    ");
+}
+
+#[test]
+fn metrics_in_json() {
+    let ctx = TestContext::new();
+    ctx.root
+        .child("a.txt")
+        .write_str("héllo wörld\nfoo\n")
+        .unwrap();
+
+    let output = ctx
+        .command()
+        .args(["--json", "-c", "-m", "-w"])
+        .output()
+        .expect("Failed to run kount");
+
+    assert!(output.status.success());
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert_eq!(json["files"][0]["lines"], 2);
+    assert_eq!(json["files"][0]["words"], 3);
+    assert_eq!(json["files"][0]["chars"], 16);
+    assert_eq!(json["files"][0]["bytes"], 18);
+    assert_eq!(json["total_words"], 3);
+    assert_eq!(json["by_extension"][0]["total_bytes"], 18);
+}
+
+#[test]
+fn metrics_omitted_from_json_by_default() {
+    let ctx = TestContext::new();
+    ctx.create_file("a.rs", 2);
+
+    let output = ctx
+        .command()
+        .arg("--json")
+        .output()
+        .expect("Failed to run kount");
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("Invalid JSON output");
+    assert!(json["files"][0].get("bytes").is_none());
+    assert!(json.get("total_words").is_none());
+}
+
+#[test]
+fn metrics_for_stdin() {
+    let ctx = TestContext::new();
+
+    kount_snapshot!(ctx.filters(), ctx.command().args(["--wc", "-"]).write_stdin("a b\nc\n"), @"
+    success: true
+    exit_code: 0
+    ----- stdout -----
+    Lines  Words  Bytes  File
+    ─────  ─────  ─────  ──────────────────────────────
+        2      3      6  <stdin>
+    ─────  ─────  ─────  ──────────────────────────────
+        2      3      6  total (1 files)
+
+    ----- stderr -----
+    ");
+}
@@ -1,7 +1,10 @@
 use std::path::PathBuf;
+use std::time::SystemTime;
 
-use clap::{Parser, ValueEnum};
-use kount_count::{CountConfig, SortOrder};
+use clap::error::ErrorKind;
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use kount_count::markers::DEFAULT_MARKERS;
+use kount_count::{CountConfig, Language, Metrics, SortOrder, TotalsScope};
 
 #[derive(Debug, Clone, ValueEnum)]
 pub enum SortBy {
@@ -10,11 +13,63 @@ pub enum SortBy {
     None,
 }
 
+#[derive(Debug, Clone, ValueEnum)]
+pub enum Totals {
+    Filtered,
+    All,
+}
+
 /// Count lines in files and directories
 #[derive(Debug, Parser)]
-#[command(name = "kount", version, about)]
+#[command(name = "kount", version, about, args_conflicts_with_subcommands = true)]
 pub struct Args {
-    /// Files or directories to count (default: current directory)
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub count: CountArgs,
+
+    /// Output as JSON
+    #[arg(long)]
+    pub json: bool,
+
+    /// Show only totals and per-extension breakdown
+    #[arg(long)]
+    pub summary: bool,
+
+    /// Show only the top N files by line count (extensions with --summary)
+    #[arg(long, value_name = "N")]
+    pub top: Option<usize>,
+
+    /// Show only the top N extensions by line count, rolling up the rest
+    #[arg(long, value_name = "N")]
+    pub top_ext: Option<usize>,
+
+    /// Add a column with each file's share of the total lines
+    #[arg(long)]
+    pub percent: bool,
+
+    /// Keep running and recount when files change
+    #[arg(long, conflicts_with_all = ["json", "files_from"])]
+    pub watch: bool,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Explore the counts in an interactive terminal interface
+    Tui(TuiArgs),
+}
+
+#[derive(Debug, clap::Args)]
+pub struct TuiArgs {
+    #[command(flatten)]
+    pub count: CountArgs,
+}
+
+/// Options that control which files are counted.
+#[derive(Debug, clap::Args)]
+pub struct CountArgs {
+    /// Files or directories to count, `-` for stdin (default: current directory)
     #[arg()]
     pub paths: Vec<PathBuf>,
 
@@ -34,20 +89,185 @@ pub struct Args {
     #[arg(long = "no-ignore")]
     pub no_ignore: bool,
 
-    /// Output as JSON
+    /// Read the files to count from FILE instead of walking (`-` for stdin)
+    #[arg(long = "files-from", value_name = "FILE", conflicts_with = "paths")]
+    pub files_from: Option<PathBuf>,
+
+    /// Paths in --files-from are separated by NUL instead of newlines
+    #[arg(short = '0', long = "null", requires = "files_from")]
+    pub null: bool,
+
+    /// File name to report for content read from stdin
+    #[arg(long = "stdin-name", value_name = "NAME")]
+    pub stdin_name: Option<PathBuf>,
+
+    /// Language of content read from stdin (e.g. rust, python, js)
+    #[arg(long = "lang", value_name = "LANG", value_parser = parse_language)]
+    pub lang: Option<&'static Language>,
+
+    /// Count the files inside tar, tar.gz and zip archives
     #[arg(long)]
-    pub json: bool,
+    pub archives: bool,
 
-    /// Show only totals and per-extension breakdown
+    /// Count the uncompressed lines of .gz, .zst, .bz2 and .xz files
     #[arg(long)]
-    pub summary: bool,
+    pub decompress: bool,
+
+    /// Only show files with at least N lines
+    #[arg(long, value_name = "N")]
+    pub min_lines: Option<u64>,
+
+    /// Only show files with at most N lines
+    #[arg(long, value_name = "N")]
+    pub max_lines: Option<u64>,
+
+    /// Compute totals over the files in the line range or all counted files
+    #[arg(long, default_value = "filtered")]
+    pub totals: Totals,
+
+    /// Skip files larger than SIZE without reading them (e.g. 512K, 10M, 2G)
+    #[arg(long, value_name = "SIZE", value_parser = parse_size)]
+    pub max_filesize: Option<u64>,
+
+    /// Only count files modified within DURATION or since DATE (e.g. 30d, 2024-06-01)
+    #[arg(long, value_name = "WHEN", value_parser = parse_time)]
+    pub newer_than: Option<SystemTime>,
 
-    /// Show only the top N files by line count
+    /// Only count files last modified more than DURATION ago or before DATE
+    #[arg(long, value_name = "WHEN", value_parser = parse_time)]
+    pub older_than: Option<SystemTime>,
+
+    /// Descend at most N directories below each path
+    #[arg(long, value_name = "N")]
+    pub max_depth: Option<usize>,
+
+    /// Follow symbolic links, reporting links that loop back
+    #[arg(short = 'L', long)]
+    pub follow_symlinks: bool,
+
+    /// Don't cross into other file systems, such as mounted volumes
     #[arg(long)]
-    pub top: Option<usize>,
+    pub one_file_system: bool,
+
+    /// Count identical files and hardlinks once, listing the duplicates
+    #[arg(long)]
+    pub dedup: bool,
+
+    /// Count generated files, such as lockfiles and protobuf stubs
+    #[arg(long)]
+    pub include_generated: bool,
+
+    /// Count third-party code in directories such as vendor/
+    #[arg(long)]
+    pub include_vendored: bool,
+
+    /// Leave out minified files, such as bundled JavaScript and CSS
+    #[arg(long)]
+    pub skip_minified: bool,
+
+    /// Also count bytes
+    #[arg(short = 'c', long)]
+    pub bytes: bool,
+
+    /// Also count UTF-8 characters
+    #[arg(short = 'm', long)]
+    pub chars: bool,
+
+    /// Also count whitespace-separated words
+    #[arg(short = 'w', long)]
+    pub words: bool,
+
+    /// Count lines, words and bytes, like `wc`
+    #[arg(long)]
+    pub wc: bool,
+
+    /// Report line lengths and the files with lines longer than N characters
+    #[arg(long, value_name = "N")]
+    pub max_line_length: Option<u64>,
+
+    /// Split lines into code, comments, doc comments and blanks
+    #[arg(long)]
+    pub classify: bool,
+
+    /// Count TODO, FIXME, HACK and XXX in comments, or the comma-separated WORDS
+    #[arg(
+        long,
+        value_name = "WORDS",
+        num_args = 0..=1,
+        require_equals = true,
+        value_delimiter = ','
+    )]
+    pub markers: Option<Vec<String>>,
+
+    /// List the line of each marker counted by --markers
+    #[arg(long, requires = "markers")]
+    pub marker_locations: bool,
+}
+
+fn parse_language(name: &str) -> Result<&'static Language, String> {
+    Language::from_name(name).ok_or_else(|| format!("unknown language `{name}`"))
+}
+
+/// Parse a byte count with an optional binary suffix: `K`, `M`, `G` or `T`.
+fn parse_size(size: &str) -> Result<u64, String> {
+    let invalid = || format!("invalid size `{size}`, expected e.g. 512K, 10M or 2G");
+    let trimmed = size.trim_end_matches(['B', 'b']);
+    let trimmed = trimmed.strip_suffix(['i', 'I']).unwrap_or(trimmed);
+    let (number, shift) = match trimmed.char_indices().last() {
+        Some((i, unit)) if unit.is_ascii_alphabetic() => {
+            let shift = match unit.to_ascii_uppercase() {
+                'K' => 10,
+                'M' => 20,
+                'G' => 30,
+                'T' => 40,
+                _ => return Err(invalid()),
+            };
+            (&trimmed[..i], shift)
+        }
+        _ => (trimmed, 0),
+    };
+    let number: u64 = number.trim().parse().map_err(|_| invalid())?;
+    number.checked_mul(1 << shift).ok_or_else(invalid)
+}
+
+/// Parse a duration before now (`30d`, `2 weeks`, `P1M`) or a date or
+/// timestamp (`2024-06-01`, `2024-06-01T12:00`, `2024-06-01T12:00:00Z`).
+/// Dates without an offset are in the local time zone.
+fn parse_time(when: &str) -> Result<SystemTime, String> {
+    let zoned = if let Ok(span) = when.parse::<jiff::Span>() {
+        jiff::Zoned::now()
+            .checked_sub(span)
+            .map_err(|e| e.to_string())?
+    } else if let Ok(timestamp) = when.parse::<jiff::Timestamp>() {
+        return Ok(SystemTime::from(timestamp));
+    } else if let Ok(datetime) = when.parse::<jiff::civil::DateTime>() {
+        datetime
+            .to_zoned(jiff::tz::TimeZone::system())
+            .map_err(|e| e.to_string())?
+    } else {
+        return Err(format!(
+            "invalid time `{when}`, expected a duration like 30d or a date like 2024-06-01"
+        ));
+    };
+    Ok(SystemTime::from(zoned.timestamp()))
 }
 
 impl Args {
+    /// Check the conflicts clap can't express, between options and the
+    /// values of others.
+    pub fn check_conflicts(&self) -> Result<(), clap::Error> {
+        let stdin = self.count.paths.iter().any(|path| path.as_os_str() == "-");
+        if self.watch && stdin {
+            return Err(Self::command().error(
+                ErrorKind::ArgumentConflict,
+                "the argument '--watch' cannot be used with reading from stdin ('-')",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl CountArgs {
     pub fn to_config(&self) -> CountConfig {
         CountConfig {
             paths: self.paths.clone(),
@@ -59,6 +279,48 @@ impl Args {
                 SortBy::Name => SortOrder::Name,
                 SortBy::None => SortOrder::None,
             },
+            file_list: None,
+            stdin_name: self.stdin_name.clone(),
+            stdin_language: self.lang,
+            archives: self.archives,
+            decompress: self.decompress,
+            metrics: self.metrics(),
+            min_lines: self.min_lines,
+            max_lines: self.max_lines,
+            totals: match self.totals {
+                Totals::Filtered => TotalsScope::Filtered,
+                Totals::All => TotalsScope::All,
+            },
+            max_filesize: self.max_filesize,
+            newer_than: self.newer_than,
+            older_than: self.older_than,
+            max_depth: self.max_depth,
+            follow_symlinks: self.follow_symlinks,
+            one_file_system: self.one_file_system,
+            dedup: self.dedup,
+            include_generated: self.include_generated,
+            include_vendored: self.include_vendored,
+            skip_minified: self.skip_minified,
+        }
+    }
+
+    pub fn metrics(&self) -> Metrics {
+        Metrics {
+            bytes: self.bytes || self.wc,
+            chars: self.chars,
+            words: self.words || self.wc,
+            max_line_length: self.max_line_length,
+            classify: self.classify,
+            language: None,
+            markers: self.markers.as_ref().map(|words| {
+                if words.is_empty() {
+                    DEFAULT_MARKERS.iter().map(ToString::to_string).collect()
+                } else {
+                    words.iter().cloned().collect()
+                }
+            }),
+            marker_locations: self.marker_locations,
+            test_modules: false,
         }
     }
 }
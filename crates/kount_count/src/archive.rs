@@ -0,0 +1,150 @@
+use std::fs::File;
+use std::io::{self, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use flate2::read::MultiGzDecoder;
+use ignore::overrides::Override;
+
+use crate::counter::{count_reader, Metrics};
+use crate::types::FileCount;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Tar,
+    TarGz,
+    Zip,
+}
+
+pub(crate) const SUFFIXES: &[(&str, ArchiveKind)] = &[
+    (".tar.gz", ArchiveKind::TarGz),
+    (".tgz", ArchiveKind::TarGz),
+    (".tar", ArchiveKind::Tar),
+    (".zip", ArchiveKind::Zip),
+];
+
+impl ArchiveKind {
+    /// Recognise an archive by its file name.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let name = path.file_name()?.to_str()?.to_ascii_lowercase();
+        SUFFIXES
+            .iter()
+            .find(|(suffix, _)| name.ends_with(suffix))
+            .map(|(_, kind)| *kind)
+    }
+}
+
+/// Count every regular file inside an archive that passes `overrides` and
+/// is at most `max_size` bytes, by the size its header gives.
+///
+/// Entries are reported as `archive!/path/inside`. An entry that can't be
+/// read is reported and skipped, keeping the others.
+pub fn count_archive(
+    path: &Path,
+    kind: ArchiveKind,
+    overrides: Option<&Override>,
+    max_size: Option<u64>,
+    metrics: &Metrics,
+) -> io::Result<Vec<FileCount>> {
+    let file = BufReader::new(File::open(path)?);
+    let filter = EntryFilter {
+        overrides,
+        max_size,
+    };
+    match kind {
+        ArchiveKind::Tar => count_tar(path, file, &filter, metrics),
+        ArchiveKind::TarGz => count_tar(path, MultiGzDecoder::new(file), &filter, metrics),
+        ArchiveKind::Zip => count_zip(path, file, &filter, metrics),
+    }
+}
+
+/// Which entries of an archive are counted.
+struct EntryFilter<'a> {
+    overrides: Option<&'a Override>,
+    max_size: Option<u64>,
+}
+
+impl EntryFilter<'_> {
+    fn includes(&self, inner: &Path, size: u64) -> bool {
+        self.max_size.is_none_or(|max| size <= max)
+            && self
+                .overrides
+                .is_none_or(|o| !o.matched(inner, false).is_ignore())
+    }
+}
+
+fn count_tar(
+    archive: &Path,
+    reader: impl Read,
+    filter: &EntryFilter,
+    metrics: &Metrics,
+) -> io::Result<Vec<FileCount>> {
+    let mut files = Vec::new();
+    // The entries end after the first one that can't be read
+    for entry in tar::Archive::new(reader).entries()? {
+        let mut entry = match entry {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("warning: {}: {e}", archive.display());
+                continue;
+            }
+        };
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let inner = match entry.path() {
+            Ok(inner) => inner.into_owned(),
+            Err(e) => {
+                eprintln!("warning: {}: {e}", archive.display());
+                continue;
+            }
+        };
+        if !filter.includes(&inner, entry.size()) {
+            continue;
+        }
+        let path = entry_path(archive, &inner);
+        match count_reader(&mut entry, &metrics.for_path(&inner)) {
+            Ok(counts) => files.push(FileCount::new(path, counts)),
+            Err(e) => eprintln!("warning: {}: {e}", path.display()),
+        }
+    }
+    Ok(files)
+}
+
+fn count_zip(
+    archive: &Path,
+    reader: impl Read + io::Seek,
+    filter: &EntryFilter,
+    metrics: &Metrics,
+) -> io::Result<Vec<FileCount>> {
+    let mut zip = zip::ZipArchive::new(reader)?;
+    let mut files = Vec::new();
+    for index in 0..zip.len() {
+        let mut entry = match zip.by_index(index) {
+            Ok(entry) => entry,
+            Err(e) => {
+                eprintln!("warning: {}: {e}", archive.display());
+                continue;
+            }
+        };
+        if !entry.is_file() {
+            continue;
+        }
+        let Some(inner) = entry.enclosed_name() else {
+            continue;
+        };
+        if !filter.includes(&inner, entry.size()) {
+            continue;
+        }
+        let path = entry_path(archive, &inner);
+        match count_reader(&mut entry, &metrics.for_path(&inner)) {
+            Ok(counts) => files.push(FileCount::new(path, counts)),
+            Err(e) => eprintln!("warning: {}: {e}", path.display()),
+        }
+    }
+    Ok(files)
+}
+
+fn entry_path(archive: &Path, inner: &Path) -> PathBuf {
+    let inner = inner.to_string_lossy().replace('\\', "/");
+    PathBuf::from(format!("{}!/{inner}", archive.display()))
+}
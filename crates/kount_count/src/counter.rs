@@ -1,21 +1,666 @@
-use std::fs::File;
-use std::io::{self, BufRead, BufReader};
+use std::fs::{File, Metadata};
+use std::io::{self, BufRead, BufReader, Read};
+use std::mem;
 use std::path::Path;
+use std::sync::Arc;
 
-pub fn count_lines(path: &Path) -> io::Result<u64> {
+use memmap2::{Mmap, MmapOptions};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::decompress::Compression;
+use crate::detect::{has_generated_marker, HEADER_LEN};
+use crate::embedded::{embeds, EmbeddedBlocks, EmbeddedCount};
+use crate::encoding::{detect, Encoding, Transcoder, SAMPLE_LEN};
+use crate::language::Language;
+use crate::markers::{count_markers, find_line_markers, MarkerCounts, MarkerLocation};
+use crate::syntax::{self, Lexer, LineKinds};
+use crate::test_code::TestModules;
+use crate::types::extension_of;
+
+/// Regular files at least this large are memory-mapped and split into chunks
+/// counted in parallel. Below it, or on a single thread, buffered reads are as
+/// fast as a map (see `cargo run -p kount_dev bench-count`).
+pub const PARALLEL_THRESHOLD: u64 = 64 << 20;
+
+/// Size of the chunks a file is split into for parallel counting.
+const CHUNK_SIZE: usize = 8 << 20;
+
+/// How the bytes of a file are fed to the line counter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Strategy {
+    /// Read through a 64 KiB buffer; works for pipes and special files.
+    Buffered,
+    /// Map the whole file into memory and count it on one thread.
+    ///
+    /// Never picked automatically; kept for benchmarking against the others.
+    Mmap,
+    /// Map the whole file and count chunks of it across rayon threads.
+    ParallelMmap,
+}
+
+impl Strategy {
+    pub fn for_metadata(metadata: &Metadata) -> Self {
+        if !metadata.is_file() {
+            return Self::Buffered;
+        }
+        if metadata.len() >= PARALLEL_THRESHOLD && rayon::current_num_threads() > 1 {
+            Self::ParallelMmap
+        } else {
+            Self::Buffered
+        }
+    }
+}
+
+/// The metrics to compute besides lines.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Metrics {
+    pub bytes: bool,
+    /// UTF-8 characters; each invalid byte counts as one character.
+    pub chars: bool,
+    /// Runs of non-whitespace, as counted by `wc -w`.
+    pub words: bool,
+    /// Measure line lengths, counting the lines longer than this.
+    pub max_line_length: Option<u64>,
+    /// Split lines into code, comments and blanks.
+    pub classify: bool,
+    /// The language whose syntax `classify` follows; plain text if unknown.
+    pub language: Option<&'static Language>,
+    /// Count these words in comments.
+    pub markers: Option<Arc<[String]>>,
+    /// Keep the lines `markers` were found on.
+    pub marker_locations: bool,
+    /// Count the lines of `#[cfg(test)]` modules in Rust, which needs the
+    /// files lexed.
+    pub test_modules: bool,
+}
+
+impl Metrics {
+    /// Take `language` from the extension of `path`.
+    #[must_use]
+    pub fn for_path(&self, path: &Path) -> Self {
+        Self {
+            language: Language::from_extension(&extension_of(path)),
+            ..self.clone()
+        }
+    }
+
+    /// Whether the stream has to be counted in order, in one pass.
+    pub fn sequential(&self) -> bool {
+        self.lexes() || embeds(self.language)
+    }
+
+    /// Whether lines are run through a `Lexer`, which needs them in order.
+    pub fn lexes(&self) -> bool {
+        self.classify || self.markers.is_some() || self.test_modules()
+    }
+
+    /// Whether the lines of `#[cfg(test)]` modules are counted, in Rust.
+    fn test_modules(&self) -> bool {
+        self.test_modules && self.language.is_some_and(|l| l.name == "Rust")
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Counts {
+    pub lines: u64,
+    pub bytes: Option<u64>,
+    pub chars: Option<u64>,
+    pub words: Option<u64>,
+    pub line_length: Option<LineLengths>,
+    pub line_kinds: Option<LineKinds>,
+    pub markers: Option<MarkerCounts>,
+    pub marker_locations: Vec<MarkerLocation>,
+    /// Whether the content looks minified: long lines with little whitespace.
+    pub minified: bool,
+    pub encoding: Encoding,
+    /// Whether the content starts with a byte order mark.
+    pub bom: bool,
+    /// Whether the start of the content has a comment like `@generated`.
+    pub generated: bool,
+    /// Lines of `#[cfg(test)]` modules, in Rust.
+    pub test_lines: u64,
+    /// The languages embedded in a Markdown, HTML, Vue or Svelte file.
+    pub embedded: Vec<EmbeddedCount>,
+}
+
+/// Content whose lines average more than this many bytes may be minified.
+const MINIFIED_LINE_LEN: u64 = 500;
+
+/// How many bytes at the start of a stream are checked for whitespace.
+const WHITESPACE_SAMPLE: usize = 4096;
+
+/// Line-length statistics, in characters without the line terminator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct LineLengths {
+    pub max: u64,
+    pub mean: f64,
+    /// The `--max-line-length` the lines were checked against.
+    pub limit: u64,
+    /// Lines longer than `limit`.
+    pub over_limit: u64,
+    #[serde(skip)]
+    pub(crate) chars: u64,
+    #[serde(skip)]
+    pub(crate) lines: u64,
+}
+
+impl LineLengths {
+    pub(crate) fn new(max: u64, limit: u64, over_limit: u64, chars: u64, lines: u64) -> Self {
+        #[allow(clippy::cast_precision_loss)]
+        let mean = if lines == 0 {
+            0.0
+        } else {
+            chars as f64 / lines as f64
+        };
+        Self {
+            max,
+            mean,
+            limit,
+            over_limit,
+            chars,
+            lines,
+        }
+    }
+}
+
+/// Line-length state of one chunk. The lines cut by the chunk's edges are
+/// only complete once its neighbours are merged in.
+#[derive(Debug, Clone, Copy, Default)]
+struct LineScan {
+    /// Whether a newline was seen.
+    closed: bool,
+    /// Characters before the first newline, or in the whole chunk.
+    head: u64,
+    /// Characters after the last newline.
+    tail: u64,
+    chars: u64,
+    /// Longest complete line.
+    max: u64,
+    over_limit: u64,
+}
+
+impl LineScan {
+    fn feed(&mut self, buf: &[u8], limit: u64) {
+        for &byte in buf {
+            if byte == b'\n' {
+                if self.closed {
+                    self.end_line(self.tail, limit);
+                } else {
+                    self.closed = true;
+                }
+                self.tail = 0;
+            } else if byte != b'\r' && !is_continuation_byte(byte) {
+                self.chars += 1;
+                if self.closed {
+                    self.tail += 1;
+                } else {
+                    self.head += 1;
+                }
+            }
+        }
+    }
+
+    fn end_line(&mut self, len: u64, limit: u64) {
+        self.max = self.max.max(len);
+        self.over_limit += u64::from(len > limit);
+    }
+
+    fn merge(mut self, next: Self, limit: u64) -> Self {
+        self.chars += next.chars;
+        match (self.closed, next.closed) {
+            (false, _) => {
+                self.head += next.head;
+                self.closed = next.closed;
+                self.tail = next.tail;
+                self.max = next.max;
+                self.over_limit = next.over_limit;
+            }
+            (true, false) => self.tail += next.head,
+            (true, true) => {
+                self.end_line(self.tail + next.head, limit);
+                self.max = self.max.max(next.max);
+                self.over_limit += next.over_limit;
+                self.tail = next.tail;
+            }
+        }
+        self
+    }
+
+    /// `newlines` is the number of newlines in the whole stream.
+    fn finish(mut self, newlines: u64, limit: u64) -> LineLengths {
+        let last = if self.closed {
+            self.end_line(self.head, limit);
+            self.tail
+        } else {
+            self.head
+        };
+        if last > 0 {
+            self.end_line(last, limit);
+        }
+        let lines = newlines + u64::from(last > 0);
+        LineLengths::new(self.max, limit, self.over_limit, self.chars, lines)
+    }
+}
+
+fn is_continuation_byte(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// Feeds the complete lines of a stream to a `Lexer`, holding on to a line
+/// cut by the end of a buffer until the rest of it arrives. Looks for
+/// `markers` in the comments it finds, and for Rust test modules.
+#[derive(Debug, Clone)]
+struct LineClassifier {
+    lexer: Lexer,
+    partial: Vec<u8>,
+    kinds: LineKinds,
+    markers: Option<Arc<[String]>>,
+    /// Lines classified so far.
+    line: u64,
+    found: Vec<MarkerLocation>,
+    tests: Option<TestModules>,
+}
+
+impl LineClassifier {
+    fn new(metrics: &Metrics) -> Self {
+        Self {
+            lexer: Lexer::new(metrics.language.map_or(&syntax::PLAIN, |l| l.syntax)),
+            partial: Vec::new(),
+            kinds: LineKinds::default(),
+            markers: metrics.markers.clone(),
+            line: 0,
+            found: Vec::new(),
+            tests: metrics.test_modules().then(TestModules::new),
+        }
+    }
+
+    fn feed(&mut self, mut buf: &[u8]) {
+        while let Some(end) = buf.iter().position(|&b| b == b'\n') {
+            if self.partial.is_empty() {
+                self.classify(&buf[..end]);
+            } else {
+                let mut line = mem::take(&mut self.partial);
+                line.extend_from_slice(&buf[..end]);
+                self.classify(&line);
+                line.clear();
+                self.partial = line;
+            }
+            buf = &buf[end + 1..];
+        }
+        self.partial.extend_from_slice(buf);
+    }
+
+    fn classify(&mut self, line: &[u8]) {
+        self.line += 1;
+        // A UTF-8 byte order mark isn't part of the first line's text
+        let line = if self.line == 1 {
+            line.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(line)
+        } else {
+            line
+        };
+        let kind = self.lexer.classify(line);
+        self.kinds.add(kind);
+        if let Some(tests) = &mut self.tests {
+            tests.feed(line, kind, self.lexer.depth());
+        }
+        if let Some(markers) = &self.markers {
+            find_line_markers(line, self.line, &self.lexer, markers, &mut self.found);
+        }
+    }
+
+    /// Lex the last line, if it has no newline, for its markers. Like
+    /// `lines`, the kinds don't count it, so that they add up.
+    fn finish(mut self) -> (LineKinds, Vec<MarkerLocation>, u64) {
+        let line = mem::take(&mut self.partial);
+        if !line.is_empty() {
+            let kinds = self.kinds;
+            self.classify(&line);
+            self.kinds = kinds;
+        }
+        let test_lines = self.tests.map_or(0, TestModules::finish);
+        (self.kinds, self.found, test_lines)
+    }
+}
+
+/// Accumulates `Counts` over consecutive chunks of one stream. Lines are only
+/// classified when the chunks are fed in order, never merged.
+#[derive(Debug, Clone)]
+struct Counter {
+    metrics: Metrics,
+    lines: u64,
+    bytes: u64,
+    chars: u64,
+    words: u64,
+    /// Whether the first byte fed was part of a word, used to merge chunks.
+    starts_in_word: Option<bool>,
+    /// Whether the last byte fed was part of a word.
+    in_word: bool,
+    line_scan: LineScan,
+    classifier: Option<LineClassifier>,
+    /// Bytes of the whitespace sample taken so far.
+    sampled: usize,
+    /// Whitespace bytes in the sample.
+    whitespace: usize,
+    /// The first `HEADER_LEN` bytes, searched for generator comments.
+    header: Vec<u8>,
+    /// Finds the code embedded in Markdown, HTML, Vue and Svelte files.
+    embedded: Option<EmbeddedBlocks>,
+}
+
+impl Counter {
+    fn new(metrics: &Metrics) -> Self {
+        Self {
+            metrics: metrics.clone(),
+            lines: 0,
+            bytes: 0,
+            chars: 0,
+            words: 0,
+            starts_in_word: None,
+            in_word: false,
+            line_scan: LineScan::default(),
+            classifier: metrics.lexes().then(|| LineClassifier::new(metrics)),
+            sampled: 0,
+            whitespace: 0,
+            header: Vec::new(),
+            embedded: EmbeddedBlocks::new(metrics),
+        }
+    }
+
+    fn feed(&mut self, buf: &[u8]) {
+        self.lines += bytecount::count(buf, b'\n') as u64;
+        self.bytes += buf.len() as u64;
+        if self.sampled < WHITESPACE_SAMPLE {
+            let sample = &buf[..buf.len().min(WHITESPACE_SAMPLE - self.sampled)];
+            self.sampled += sample.len();
+            self.whitespace += sample.iter().filter(|&&b| is_word_separator(b)).count();
+        }
+        if self.header.len() < HEADER_LEN {
+            let rest = HEADER_LEN - self.header.len();
+            self.header.extend_from_slice(&buf[..buf.len().min(rest)]);
+        }
+        if self.metrics.chars {
+            self.chars += bytecount::num_chars(buf) as u64;
+        }
+        if self.metrics.words && !buf.is_empty() {
+            self.starts_in_word
+                .get_or_insert(!is_word_separator(buf[0]));
+            for &byte in buf {
+                let in_word = !is_word_separator(byte);
+                if in_word && !self.in_word {
+                    self.words += 1;
+                }
+                self.in_word = in_word;
+            }
+        }
+        if let Some(limit) = self.metrics.max_line_length {
+            self.line_scan.feed(buf, limit);
+        }
+        if let Some(classifier) = &mut self.classifier {
+            classifier.feed(buf);
+        }
+        if let Some(embedded) = &mut self.embedded {
+            embedded.feed(buf);
+        }
+    }
+
+    /// Combine with the counter of the chunk directly following this one.
+    fn merge(self, next: &Self) -> Self {
+        let split_word = self.in_word && next.starts_in_word == Some(true);
+        // Keep the sample and header of the first chunk, as a single pass would
+        let first = if self.bytes == 0 { next } else { &self };
+        Self {
+            metrics: self.metrics.clone(),
+            lines: self.lines + next.lines,
+            bytes: self.bytes + next.bytes,
+            chars: self.chars + next.chars,
+            words: self.words + next.words - u64::from(split_word),
+            starts_in_word: self.starts_in_word.or(next.starts_in_word),
+            in_word: if next.starts_in_word.is_some() {
+                next.in_word
+            } else {
+                self.in_word
+            },
+            line_scan: self
+                .line_scan
+                .merge(next.line_scan, self.metrics.max_line_length.unwrap_or(0)),
+            classifier: None,
+            sampled: first.sampled,
+            whitespace: first.whitespace,
+            header: first.header.clone(),
+            embedded: None,
+        }
+    }
+
+    fn finish(self) -> Counts {
+        let minified = self.is_minified();
+        let (kinds, mut found, test_lines) = self
+            .classifier
+            .map(LineClassifier::finish)
+            .unwrap_or_default();
+        let (embedded, in_blocks) = self
+            .embedded
+            .map(EmbeddedBlocks::finish)
+            .unwrap_or_default();
+        // Comments the file's own syntax already found them in aren't counted twice
+        let in_blocks: Vec<_> = in_blocks
+            .into_iter()
+            .filter(|location| !found.contains(location))
+            .collect();
+        found.extend(in_blocks);
+        found.sort_by_key(|location| location.line);
+        let markers = self
+            .metrics
+            .markers
+            .as_ref()
+            .map(|markers| count_markers(markers, &found));
+        Counts {
+            lines: self.lines,
+            bytes: self.metrics.bytes.then_some(self.bytes),
+            chars: self.metrics.chars.then_some(self.chars),
+            words: self.metrics.words.then_some(self.words),
+            line_length: self
+                .metrics
+                .max_line_length
+                .map(|limit| self.line_scan.finish(self.lines, limit)),
+            minified,
+            generated: has_generated_marker(&self.header, self.metrics.language),
+            test_lines,
+            embedded,
+            line_kinds: self.metrics.classify.then_some(kinds),
+            markers,
+            marker_locations: if self.metrics.marker_locations {
+                found
+            } else {
+                Vec::new()
+            },
+            ..Counts::default()
+        }
+    }
+
+    /// Long lines on average, and less than a tenth of the sample whitespace.
+    fn is_minified(&self) -> bool {
+        self.bytes / self.lines.max(1) > MINIFIED_LINE_LEN && self.whitespace * 10 < self.sampled
+    }
+}
+
+fn is_word_separator(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t'..=b'\r')
+}
+
+pub fn count_lines(path: &Path, metrics: &Metrics) -> io::Result<Counts> {
     let file = File::open(path)?;
-    let mut reader = BufReader::with_capacity(64 * 1024, file);
-    let mut count = 0u64;
+    let strategy = Strategy::for_metadata(&file.metadata()?);
+    count_file(&file, strategy, metrics)
+}
+
+/// Count an open file using the given strategy. UTF-16 and UTF-32 files, and
+/// files whose lines are classified or searched for embedded code, are always
+/// counted on one thread.
+pub fn count_file(file: &File, strategy: Strategy, metrics: &Metrics) -> io::Result<Counts> {
+    if strategy == Strategy::Buffered || metrics.sequential() {
+        return count_reader(file, metrics);
+    }
+    let map = map(file)?;
+    let (encoding, bom) = detect(&map);
+    if !encoding.is_utf8() {
+        return count_reader(&map[..], metrics);
+    }
+    let counter = if strategy == Strategy::Mmap {
+        count_chunk(&map, metrics)
+    } else {
+        map.par_chunks(CHUNK_SIZE)
+            .map(|chunk| count_chunk(chunk, metrics))
+            .reduce(|| Counter::new(metrics), |a, b| a.merge(&b))
+    };
+    Ok(Counts {
+        bom: bom > 0,
+        ..counter.finish()
+    })
+}
+
+fn map(file: &File) -> io::Result<Mmap> {
+    // SAFETY: the map is only read while counting. If another process
+    // truncates the file meanwhile we may see a SIGBUS, the same trade-off
+    // ripgrep makes for its mmap searcher.
+    let map = unsafe { MmapOptions::new().populate().map(file)? };
+    #[cfg(unix)]
+    map.advise(memmap2::Advice::Sequential).ok();
+    Ok(map)
+}
+
+fn count_chunk(bytes: &[u8], metrics: &Metrics) -> Counter {
+    let mut counter = Counter::new(metrics);
+    counter.feed(bytes);
+    counter
+}
+
+/// Count the uncompressed content of `path`.
+pub fn count_lines_decompressed(
+    path: &Path,
+    compression: Compression,
+    metrics: &Metrics,
+) -> io::Result<Counts> {
+    let file = BufReader::new(File::open(path)?);
+    count_reader(compression.decoder(file)?, metrics)
+}
+
+/// Count a stream, decoding it to UTF-8 first if it is UTF-16 or UTF-32.
+pub fn count_reader(reader: impl Read, metrics: &Metrics) -> io::Result<Counts> {
+    let mut reader = BufReader::with_capacity(64 * 1024, reader);
+    let mut counter = Counter::new(metrics);
+    // Pipes and decoders may hand over less than the sample at a time
+    let mut head = Vec::with_capacity(SAMPLE_LEN);
+    reader
+        .by_ref()
+        .take(SAMPLE_LEN as u64)
+        .read_to_end(&mut head)?;
+    let (encoding, bom) = detect(&head);
+    let mut transcoder = None;
+    let mut start = 0;
+    if !encoding.is_utf8() {
+        // The BOM isn't a character of the decoded text
+        start = bom;
+        transcoder = Some(Transcoder::new(encoding));
+    }
+    let mut raw_bytes = head.len() as u64;
+    let mut feed = |buf: &[u8]| match &mut transcoder {
+        Some(transcoder) => counter.feed(transcoder.feed(buf)),
+        None => counter.feed(buf),
+    };
+    feed(&head[start..]);
 
     loop {
         let buf = reader.fill_buf()?;
         if buf.is_empty() {
             break;
         }
-        count += bytecount::count(buf, b'\n') as u64;
+        feed(buf);
         let len = buf.len();
+        raw_bytes += len as u64;
         reader.consume(len);
     }
+    if let Some(transcoder) = &mut transcoder {
+        counter.feed(transcoder.finish());
+    }
+
+    let counts = counter.finish();
+    Ok(Counts {
+        // `wc -c` counts the bytes on disk, not the decoded ones
+        bytes: counts.bytes.map(|_| raw_bytes),
+        encoding,
+        bom: bom > 0,
+        ..counts
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{self, Read, Seek, SeekFrom, Write};
+
+    use super::{count_file, count_reader, Metrics, Strategy, CHUNK_SIZE};
+    use crate::encoding::Encoding;
+
+    #[test]
+    fn metrics_agree_across_chunks() -> io::Result<()> {
+        // Larger than a chunk, so lines and words are split between chunks
+        let mut content = Vec::new();
+        let mut row = 0u64;
+        while content.len() < 2 * CHUNK_SIZE {
+            writeln!(
+                content,
+                "{row},kount,{},{}, a row of words",
+                row * 7,
+                row % 13
+            )?;
+            row += 1;
+        }
+        let mut file = tempfile::tempfile()?;
+        file.write_all(&content)?;
+        let metrics = Metrics {
+            bytes: true,
+            chars: true,
+            words: true,
+            max_line_length: Some(30),
+            ..Metrics::default()
+        };
+        let counts = [Strategy::Buffered, Strategy::Mmap, Strategy::ParallelMmap]
+            .into_iter()
+            .map(|strategy| {
+                // The buffered strategy reads from the current position
+                let file = file.try_clone()?;
+                (&file).seek(SeekFrom::Start(0))?;
+                count_file(&file, strategy, &metrics)
+            })
+            .collect::<io::Result<Vec<_>>>()?;
+        assert!(counts[0].line_length.is_some_and(|l| l.over_limit > 0));
+        assert!(counts.iter().all(|count| *count == counts[0]));
+        Ok(())
+    }
 
-    Ok(count)
+    /// Hands over one byte per read, like a slow pipe.
+    struct Trickle<'a>(&'a [u8]);
+
+    impl Read for Trickle<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            let Some((first, rest)) = self.0.split_first() else {
+                return Ok(0);
+            };
+            buf[0] = *first;
+            self.0 = rest;
+            Ok(1)
+        }
+    }
+
+    #[test]
+    fn encoding_detected_across_short_reads() -> io::Result<()> {
+        let text: Vec<u8> = "first line\nsecond line\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let counts = count_reader(Trickle(&text), &Metrics::default())?;
+        assert_eq!(counts.encoding, Encoding::Utf16Le);
+        assert_eq!(counts.lines, 2);
+        Ok(())
+    }
 }
@@ -0,0 +1,183 @@
+use std::collections::hash_map::Entry;
+use std::collections::HashMap;
+use std::fs::{self, File, Metadata};
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+
+use rayon::prelude::*;
+use serde::Serialize;
+use xxhash_rust::xxh3::Xxh3;
+
+use crate::types::FileCount;
+
+/// Files with the same content, of which only `kept` is counted.
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub lines: u64,
+    pub kept: PathBuf,
+    pub duplicates: Vec<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum FileId {
+    #[cfg(unix)]
+    Inode(u64, u64),
+    #[cfg(not(unix))]
+    Path(PathBuf),
+}
+
+#[cfg(unix)]
+fn file_id(_path: &Path, metadata: &Metadata) -> FileId {
+    use std::os::unix::fs::MetadataExt;
+    FileId::Inode(metadata.dev(), metadata.ino())
+}
+
+#[cfg(not(unix))]
+fn file_id(path: &Path, _metadata: &Metadata) -> FileId {
+    FileId::Path(path.canonicalize().unwrap_or_else(|_| path.to_path_buf()))
+}
+
+/// Drop the files that are the same file as another one (a path given twice
+/// or a hardlink) or have the same content, keeping the first path of each
+/// group. Only files on disk and content hashed while counting it take part,
+/// so archive entries are left alone, and empty files are only merged when
+/// they are the same file.
+pub fn dedup(mut files: Vec<FileCount>) -> (Vec<FileCount>, Vec<DuplicateGroup>) {
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    let keys: Vec<Option<(Option<FileId>, u64)>> = files
+        .par_iter()
+        .map(|file| {
+            if let Some((len, _)) = file.content {
+                return Some((None, len));
+            }
+            let metadata = fs::metadata(&file.path).ok()?;
+            metadata
+                .is_file()
+                .then(|| (Some(file_id(&file.path, &metadata)), metadata.len()))
+        })
+        .collect();
+
+    // Every file points at the first file it duplicates, or at itself
+    let mut leader: Vec<usize> = (0..files.len()).collect();
+    let mut by_id: HashMap<&FileId, usize> = HashMap::new();
+    for (index, id) in keys
+        .iter()
+        .enumerate()
+        .filter_map(|(i, k)| Some((i, k.as_ref()?.0.as_ref()?)))
+    {
+        match by_id.entry(id) {
+            Entry::Occupied(first) => leader[index] = *first.get(),
+            Entry::Vacant(slot) => {
+                slot.insert(index);
+            }
+        }
+    }
+
+    // Only distinct files of the same size can have the same content
+    let mut by_size: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (index, key) in keys.iter().enumerate() {
+        if let Some((_, len)) = key {
+            if *len > 0 && leader[index] == index {
+                by_size.entry(*len).or_default().push(index);
+            }
+        }
+    }
+    let mut candidates: Vec<usize> = by_size
+        .into_values()
+        .filter(|indices| indices.len() > 1)
+        .flatten()
+        .collect();
+    candidates.sort_unstable();
+    let hashes: Vec<(usize, u128)> = candidates
+        .into_par_iter()
+        .filter_map(|index| {
+            let hash = match files[index].content {
+                Some((_, hash)) => hash,
+                None => hash_file(&files[index].path).ok()?,
+            };
+            Some((index, hash))
+        })
+        .collect();
+    let mut by_hash: HashMap<u128, usize> = HashMap::new();
+    for (index, hash) in hashes {
+        leader[index] = *by_hash.entry(hash).or_insert(index);
+    }
+
+    // Leaders always come first, so one pass resolves chains
+    for index in 0..leader.len() {
+        leader[index] = leader[leader[index]];
+    }
+
+    let mut groups: HashMap<usize, Vec<PathBuf>> = HashMap::new();
+    for (index, file) in files.iter().enumerate() {
+        if leader[index] != index {
+            groups
+                .entry(leader[index])
+                .or_default()
+                .push(file.path.clone());
+        }
+    }
+    let mut duplicates: Vec<DuplicateGroup> = groups
+        .into_iter()
+        .map(|(index, duplicates)| DuplicateGroup {
+            lines: files[index].lines,
+            kept: files[index].path.clone(),
+            duplicates,
+        })
+        .collect();
+    duplicates.sort_by(|a, b| a.kept.cmp(&b.kept));
+
+    let kept = files
+        .into_iter()
+        .zip(leader.iter().enumerate())
+        .filter(|(_, (index, leader))| index == *leader)
+        .map(|(file, _)| file)
+        .collect();
+    (kept, duplicates)
+}
+
+fn hash_file(path: &Path) -> io::Result<u128> {
+    let mut reader = BufReader::with_capacity(64 * 1024, File::open(path)?);
+    let mut hasher = Xxh3::new();
+    loop {
+        let buf = reader.fill_buf()?;
+        if buf.is_empty() {
+            break;
+        }
+        hasher.update(buf);
+        let len = buf.len();
+        reader.consume(len);
+    }
+    Ok(hasher.digest128())
+}
+
+/// Hashes the content read through it, as `hash_file` would.
+pub(crate) struct HashingReader<R> {
+    inner: R,
+    hasher: Xxh3,
+    len: u64,
+}
+
+impl<R: Read> HashingReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            hasher: Xxh3::new(),
+            len: 0,
+        }
+    }
+
+    /// The length and hash of what was read.
+    pub(crate) fn finish(&self) -> (u64, u128) {
+        (self.len, self.hasher.digest128())
+    }
+}
+
+impl<R: Read> Read for HashingReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.hasher.update(&buf[..read]);
+        self.len += read as u64;
+        Ok(read)
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::language::Language;
+use crate::syntax::Lexer;
+use crate::types::{CountConfig, FileCount};
+use crate::walker::{below_root, root_paths};
+
+/// Directories whose contents are third-party code.
+const VENDORED_DIRS: &[&str] = &[
+    "vendor",
+    "vendored",
+    "third_party",
+    "third-party",
+    "thirdparty",
+    "node_modules",
+    "bower_components",
+    "Pods",
+    "Carthage",
+];
+
+/// Lockfiles and other files that are always written by a tool.
+const GENERATED_NAMES: &[&str] = &[
+    "Cargo.lock",
+    "package-lock.json",
+    "npm-shrinkwrap.json",
+    "yarn.lock",
+    "pnpm-lock.yaml",
+    "bun.lock",
+    "poetry.lock",
+    "Pipfile.lock",
+    "uv.lock",
+    "Gemfile.lock",
+    "composer.lock",
+    "go.sum",
+    "flake.lock",
+    "mix.lock",
+    "pubspec.lock",
+    "Podfile.lock",
+];
+
+/// File name endings of protobuf stubs and other code generator output.
+const GENERATED_SUFFIXES: &[&str] = &[
+    ".pb.go",
+    ".pb.cc",
+    ".pb.h",
+    ".pb.swift",
+    "_pb2.py",
+    "_pb2.pyi",
+    "_pb2_grpc.py",
+    "_pb.js",
+    "_pb.d.ts",
+    "_grpc_pb.js",
+    ".g.dart",
+    ".freezed.dart",
+    ".Designer.cs",
+    ".designer.cs",
+    ".generated.cs",
+];
+
+/// What code generators write in a comment at the top of their output, as in
+/// `@generated` or Go's `Code generated by ... DO NOT EDIT.`
+const GENERATED_MARKERS: &[&[u8]] = &[b"@generated", b"DO NOT EDIT"];
+
+/// How much of the start of a file is searched for `GENERATED_MARKERS`.
+pub(crate) const HEADER_LEN: usize = 1024;
+
+/// Lines and files of one kind of file that was left out of the totals.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct ExcludedTotals {
+    pub files: u64,
+    pub lines: u64,
+}
+
+/// Files left out because they are generated, vendored or minified. A file
+/// that is several of these is counted under the first.
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct Excluded {
+    pub generated: ExcludedTotals,
+    pub vendored: ExcludedTotals,
+    pub minified: ExcludedTotals,
+}
+
+impl Excluded {
+    pub fn is_empty(&self) -> bool {
+        self.generated.files == 0 && self.vendored.files == 0 && self.minified.files == 0
+    }
+}
+
+/// Flag generated and vendored files, then drop the ones `config` doesn't
+/// include, along with minified files if `config.skip_minified` is set.
+pub(crate) fn exclude(files: &mut Vec<FileCount>, config: &CountConfig) -> Excluded {
+    classify(files, &root_paths(config));
+
+    let mut excluded = Excluded::default();
+    files.retain(|file| {
+        let totals = if file.vendored && !config.include_vendored {
+            &mut excluded.vendored
+        } else if file.generated && !config.include_generated {
+            &mut excluded.generated
+        } else if file.minified && config.skip_minified {
+            &mut excluded.minified
+        } else {
+            return true;
+        };
+        totals.files += 1;
+        totals.lines += file.lines;
+        false
+    });
+    excluded
+}
+
+/// Set `vendored` from the path of each file below the root it was walked
+/// from, letting `linguist-generated` and `linguist-vendored` in
+/// `.gitattributes` override it and the `generated` flag set while counting.
+pub fn classify(files: &mut [FileCount], roots: &[PathBuf]) {
+    files.par_iter_mut().for_each(|file| {
+        file.vendored = is_vendored_path(below_root(&file.path, roots));
+    });
+
+    let mut attributes = GitAttributes::default();
+    for file in files {
+        let (generated, vendored) = attributes.linguist(&file.path);
+        file.generated = generated.unwrap_or(file.generated);
+        file.vendored = vendored.unwrap_or(file.vendored);
+    }
+}
+
+pub fn is_vendored_path(path: &Path) -> bool {
+    let Some(parent) = path.parent() else {
+        return false;
+    };
+    parent.components().any(|c| match c {
+        Component::Normal(name) => name.to_str().is_some_and(|n| VENDORED_DIRS.contains(&n)),
+        _ => false,
+    })
+}
+
+pub fn is_generated_name(path: &Path) -> bool {
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return false;
+    };
+    GENERATED_NAMES.contains(&name) || GENERATED_SUFFIXES.iter().any(|s| name.ends_with(s))
+}
+
+/// Names like `app.min.js` or `style.min.css`.
+pub fn is_minified_name(path: &Path) -> bool {
+    path.file_name()
+        .and_then(|n| n.to_str())
+        .is_some_and(|n| n.contains(".min."))
+}
+
+/// Whether a comment in the first `HEADER_LEN` bytes of a file in `language`
+/// carries a code generator's marker. Text outside comments doesn't count, so
+/// a README can talk about `DO NOT EDIT` without being left out.
+pub(crate) fn has_generated_marker(header: &[u8], language: Option<&Language>) -> bool {
+    let Some(language) = language else {
+        return false;
+    };
+    let header = header.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(header);
+    let mut lexer = Lexer::new(language.syntax);
+    header.split(|&b| b == b'\n').any(|line| {
+        lexer.classify(line);
+        lexer.comments().iter().any(|comment| {
+            let comment = &line[comment.clone()];
+            GENERATED_MARKERS
+                .iter()
+                .any(|marker| comment.windows(marker.len()).any(|w| w == *marker))
+        })
+    })
+}
+
+/// A `.gitattributes` line that sets or unsets a linguist attribute.
+struct Rule {
+    pattern: Gitignore,
+    generated: Option<bool>,
+    vendored: Option<bool>,
+}
+
+#[derive(Default)]
+struct Directory {
+    rules: Vec<Rule>,
+    /// Whether the directory holds a `.git`, so that no `.gitattributes` above
+    /// it applies.
+    repo_root: bool,
+}
+
+/// The `.gitattributes` files read so far, by directory.
+#[derive(Default)]
+struct GitAttributes {
+    directories: HashMap<PathBuf, Directory>,
+}
+
+impl GitAttributes {
+    /// The `linguist-generated` and `linguist-vendored` values for `path`. As
+    /// in git, deeper files and later lines take precedence.
+    fn linguist(&mut self, path: &Path) -> (Option<bool>, Option<bool>) {
+        let Ok(path) = std::path::absolute(path) else {
+            return (None, None);
+        };
+        let mut dirs = Vec::new();
+        for dir in path.ancestors().skip(1) {
+            dirs.push(dir);
+            if self.directory(dir).repo_root {
+                break;
+            }
+        }
+
+        let (mut generated, mut vendored) = (None, None);
+        for dir in dirs.into_iter().rev() {
+            for rule in &self.directory(dir).rules {
+                if rule.pattern.matched(&path, false).is_ignore() {
+                    generated = rule.generated.or(generated);
+                    vendored = rule.vendored.or(vendored);
+                }
+            }
+        }
+        (generated, vendored)
+    }
+
+    fn directory(&mut self, dir: &Path) -> &Directory {
+        self.directories
+            .entry(dir.to_path_buf())
+            .or_insert_with(|| Directory {
+                rules: fs::read_to_string(dir.join(".gitattributes"))
+                    .map(|content| parse_rules(dir, &content))
+                    .unwrap_or_default(),
+                repo_root: dir.join(".git").exists(),
+            })
+    }
+}
+
+fn parse_rules(dir: &Path, content: &str) -> Vec<Rule> {
+    let mut rules = Vec::new();
+    for line in content.lines() {
+        let mut fields = line.split_whitespace();
+        let Some(pattern) = fields.next().filter(|p| !p.starts_with('#')) else {
+            continue;
+        };
+        let (mut generated, mut vendored) = (None, None);
+        for attribute in fields {
+            let (name, value) = match attribute.split_once('=') {
+                Some((name, value)) => (name, value != "false"),
+                None => match attribute.strip_prefix('-') {
+                    Some(name) => (name, false),
+                    None => (attribute, true),
+                },
+            };
+            match name {
+                "linguist-generated" => generated = Some(value),
+                "linguist-vendored" => vendored = Some(value),
+                _ => {}
+            }
+        }
+        if generated.is_none() && vendored.is_none() {
+            continue;
+        }
+        let mut builder = GitignoreBuilder::new(dir);
+        if builder.add_line(None, pattern).is_err() {
+            continue;
+        }
+        if let Ok(pattern) = builder.build() {
+            rules.push(Rule {
+                pattern,
+                generated,
+                vendored,
+            });
+        }
+    }
+    rules
+}
@@ -0,0 +1,336 @@
+use std::mem;
+use std::sync::Arc;
+
+use serde::Serialize;
+
+use crate::counter::Metrics;
+use crate::language::Language;
+use crate::markers::{find_line_markers, MarkerLocation};
+use crate::syntax::{self, Lexer, LineKinds};
+
+/// The lines of one language embedded in a file: the code blocks of a
+/// Markdown file, or the `<script>` and `<style>` blocks of an HTML page or a
+/// Vue or Svelte component.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct EmbeddedCount {
+    /// The name of the language, or the tag the block was given if kount
+    /// doesn't know it.
+    pub language: String,
+    pub blocks: u64,
+    pub lines: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_lines: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blanks: Option<u64>,
+}
+
+impl EmbeddedCount {
+    pub(crate) fn new(language: String) -> Self {
+        Self {
+            language,
+            blocks: 0,
+            lines: 0,
+            code: None,
+            comments: None,
+            doc_lines: None,
+            blanks: None,
+        }
+    }
+
+    /// Add a block whose lines have been counted, returning the markers
+    /// found in it. Its kinds are only added if `classify` is set.
+    pub(crate) fn add_block(&mut self, block: BlockLines, classify: bool) -> Vec<MarkerLocation> {
+        self.blocks += 1;
+        self.lines += block.lines;
+        if classify {
+            add(&mut self.code, block.kinds.code);
+            add(&mut self.comments, block.kinds.comments);
+            add(&mut self.doc_lines, block.kinds.docs);
+            add(&mut self.blanks, block.kinds.blanks);
+        }
+        block.found
+    }
+}
+
+/// The lines of a block counted so far, lexed with the syntax of its language
+/// if `metrics` classifies lines or looks for markers.
+#[derive(Debug, Clone)]
+pub(crate) struct BlockLines {
+    lexer: Option<Lexer>,
+    markers: Option<Arc<[String]>>,
+    lines: u64,
+    kinds: LineKinds,
+    found: Vec<MarkerLocation>,
+}
+
+impl BlockLines {
+    pub(crate) fn new(language: Option<&'static Language>, metrics: &Metrics) -> Self {
+        let lexes = metrics.classify || metrics.markers.is_some();
+        Self {
+            lexer: lexes.then(|| Lexer::new(language.map_or(&syntax::PLAIN, |l| l.syntax))),
+            markers: metrics.markers.clone(),
+            lines: 0,
+            kinds: LineKinds::default(),
+            found: Vec::new(),
+        }
+    }
+
+    /// Add `line`, which is line `number` of the file.
+    pub(crate) fn add_line(&mut self, line: &[u8], number: u64) {
+        self.lines += 1;
+        if let Some(lexer) = &mut self.lexer {
+            self.kinds.add(lexer.classify(line));
+            if let Some(markers) = &self.markers {
+                find_line_markers(line, number, lexer, markers, &mut self.found);
+            }
+        }
+    }
+}
+
+fn add(total: &mut Option<u64>, value: u64) {
+    *total = Some(total.unwrap_or(0) + value);
+}
+
+/// How the blocks of embedded code are delimited in files of a language.
+#[derive(Debug, Clone, Copy)]
+enum Delimiters {
+    /// Code blocks fenced with three or more backticks or tildes, tagged with
+    /// the first word of their info string. Untagged blocks are left out.
+    Fences,
+    /// The contents of `tags` elements, tagged with the language given by
+    /// their `lang` or `type` attribute. With `top_level`, as in single-file
+    /// components, only elements starting at the beginning of a line count,
+    /// so that nested `<template>` elements are skipped.
+    Tags {
+        tags: &'static [&'static str],
+        top_level: bool,
+    },
+}
+
+impl Delimiters {
+    /// How the blocks are delimited in files of `language`, if it embeds
+    /// others.
+    fn of(language: &Language) -> Option<Self> {
+        Some(match language.name {
+            "Markdown" => Self::Fences,
+            "HTML" => Self::Tags {
+                tags: &["script", "style"],
+                top_level: false,
+            },
+            "Svelte" => Self::Tags {
+                tags: &["script", "style"],
+                top_level: true,
+            },
+            "Vue" => Self::Tags {
+                tags: &["template", "script", "style"],
+                top_level: true,
+            },
+            _ => return None,
+        })
+    }
+
+    /// If `line` opens a block, what closes it and the block's tag.
+    fn open(self, line: &[u8]) -> Option<(Vec<u8>, String)> {
+        match self {
+            Self::Fences => {
+                let line = line.trim_ascii();
+                let fence_len = fence_len(line)?;
+                let info = String::from_utf8_lossy(&line[fence_len..]);
+                let tag = info
+                    .trim_start_matches(['{', '.'])
+                    .split(|c: char| c.is_whitespace() || matches!(c, ',' | '}'))
+                    .next()
+                    .unwrap_or_default()
+                    .to_lowercase();
+                Some((line[..fence_len].to_vec(), tag))
+            }
+            Self::Tags { tags, top_level } => {
+                let line = if top_level {
+                    line
+                } else {
+                    line.trim_ascii_start()
+                };
+                let rest = line.strip_prefix(b"<")?;
+                let name = tags.iter().find(|name| {
+                    strip_prefix_ignore_case(rest, name.as_bytes())
+                        .is_some_and(|rest| rest.first().is_none_or(|b| b" >\t".contains(b)))
+                })?;
+                let end = format!("</{name}>").into_bytes();
+                // Elements closed on the line they open on hold no whole lines
+                if contains_ignore_case(line, &end) {
+                    return None;
+                }
+                let tag = String::from_utf8_lossy(line).to_lowercase();
+                Some((end, element_language(name, &tag)))
+            }
+        }
+    }
+
+    /// Whether `line` closes the block that `end` closes.
+    fn closes(self, end: &[u8], line: &[u8]) -> bool {
+        match self {
+            // A closing fence is at least as long as the opening one, and bare
+            Self::Fences => {
+                let line = line.trim_ascii();
+                fence_len(line).is_some_and(|len| len == line.len()) && line.starts_with(end)
+            }
+            Self::Tags {
+                top_level: true, ..
+            } => strip_prefix_ignore_case(line, end).is_some(),
+            Self::Tags {
+                top_level: false, ..
+            } => contains_ignore_case(line, end),
+        }
+    }
+}
+
+/// The length of the fence `line` starts with, if it is one.
+fn fence_len(line: &[u8]) -> Option<usize> {
+    let first = *line.first().filter(|b| matches!(b, b'`' | b'~'))?;
+    let len = line.iter().take_while(|&&b| b == first).count();
+    (len >= 3).then_some(len)
+}
+
+fn strip_prefix_ignore_case<'a>(line: &'a [u8], prefix: &[u8]) -> Option<&'a [u8]> {
+    let (start, rest) = line.split_at_checked(prefix.len())?;
+    start.eq_ignore_ascii_case(prefix).then_some(rest)
+}
+
+fn contains_ignore_case(line: &[u8], needle: &[u8]) -> bool {
+    line.windows(needle.len())
+        .any(|window| window.eq_ignore_ascii_case(needle))
+}
+
+/// Whether files of `language` have other languages embedded in them.
+pub(crate) fn embeds(language: Option<&Language>) -> bool {
+    language.and_then(Delimiters::of).is_some()
+}
+
+/// A block that has been opened and not closed yet.
+#[derive(Debug, Clone)]
+struct OpenBlock {
+    /// The fence or closing tag that ends the block.
+    end: Vec<u8>,
+    tag: String,
+    lines: BlockLines,
+}
+
+/// Finds the languages embedded in a file as its decoded text streams past,
+/// a line at a time, along with the markers in their comments. Blocks of the
+/// same language are added up, and a block still open at the end is left out.
+#[derive(Debug, Clone)]
+pub(crate) struct EmbeddedBlocks {
+    delimiters: Delimiters,
+    metrics: Metrics,
+    partial: Vec<u8>,
+    /// Lines seen so far.
+    line: u64,
+    open: Option<OpenBlock>,
+    counts: Vec<EmbeddedCount>,
+    found: Vec<MarkerLocation>,
+}
+
+impl EmbeddedBlocks {
+    /// A finder for the blocks of files in `metrics.language`, if it embeds
+    /// other languages.
+    pub(crate) fn new(metrics: &Metrics) -> Option<Self> {
+        Some(Self {
+            delimiters: metrics.language.and_then(Delimiters::of)?,
+            metrics: metrics.clone(),
+            partial: Vec::new(),
+            line: 0,
+            open: None,
+            counts: Vec::new(),
+            found: Vec::new(),
+        })
+    }
+
+    pub(crate) fn feed(&mut self, mut buf: &[u8]) {
+        while let Some(end) = buf.iter().position(|&b| b == b'\n') {
+            if self.partial.is_empty() {
+                self.add_line(&buf[..end]);
+            } else {
+                let mut line = mem::take(&mut self.partial);
+                line.extend_from_slice(&buf[..end]);
+                self.add_line(&line);
+                line.clear();
+                self.partial = line;
+            }
+            buf = &buf[end + 1..];
+        }
+        self.partial.extend_from_slice(buf);
+    }
+
+    fn add_line(&mut self, line: &[u8]) {
+        self.line += 1;
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        let Some(open) = &mut self.open else {
+            self.open = self.delimiters.open(line).map(|(end, tag)| OpenBlock {
+                lines: BlockLines::new(Language::from_name(&tag), &self.metrics),
+                end,
+                tag,
+            });
+            return;
+        };
+        if !self.delimiters.closes(&open.end, line) {
+            open.lines.add_line(line, self.line);
+            return;
+        }
+        let Some(block) = self.open.take().filter(|block| !block.tag.is_empty()) else {
+            return;
+        };
+        let language = Language::from_name(&block.tag);
+        let name = language.map_or(block.tag, |l| l.name.to_string());
+        let index = self
+            .counts
+            .iter()
+            .position(|c| c.language == name)
+            .unwrap_or_else(|| {
+                self.counts.push(EmbeddedCount::new(name));
+                self.counts.len() - 1
+            });
+        let found = self.counts[index].add_block(block.lines, self.metrics.classify);
+        self.found.extend(found);
+    }
+
+    /// The counts by language, and the markers found in the blocks.
+    pub(crate) fn finish(mut self) -> (Vec<EmbeddedCount>, Vec<MarkerLocation>) {
+        let line = mem::take(&mut self.partial);
+        if !line.is_empty() {
+            self.add_line(&line);
+        }
+        (self.counts, self.found)
+    }
+}
+
+/// The language of a `<script>`, `<style>` or `<template>` element from its
+/// opening tag.
+fn element_language(name: &str, tag: &str) -> String {
+    if let Some(lang) = attribute(tag, "lang") {
+        return lang.to_string();
+    }
+    let default = match name {
+        "script" => "javascript",
+        "style" => "css",
+        _ => "html",
+    };
+    match attribute(tag, "type") {
+        Some(kind) if kind.contains("typescript") => "typescript",
+        Some(kind) if kind.contains("json") => "json",
+        _ => default,
+    }
+    .to_string()
+}
+
+/// The value of `name="value"` or `name='value'` in an opening tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let start = tag.find(&format!(" {name}="))? + name.len() + 2;
+    let rest = &tag[start..];
+    let quote = rest.chars().next().filter(|c| matches!(c, '"' | '\''))?;
+    let value = &rest[1..];
+    value.find(quote).map(|end| &value[..end])
+}
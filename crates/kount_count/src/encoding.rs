@@ -0,0 +1,163 @@
+use serde::Serialize;
+
+/// How many bytes at the start of a stream are looked at to guess its encoding.
+pub const SAMPLE_LEN: usize = 4096;
+
+/// The text encoding of a file, as found by `detect`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub enum Encoding {
+    #[default]
+    #[serde(rename = "utf-8")]
+    Utf8,
+    #[serde(rename = "utf-16le")]
+    Utf16Le,
+    #[serde(rename = "utf-16be")]
+    Utf16Be,
+    #[serde(rename = "utf-32le")]
+    Utf32Le,
+    #[serde(rename = "utf-32be")]
+    Utf32Be,
+}
+
+impl Encoding {
+    pub fn is_utf8(&self) -> bool {
+        *self == Self::Utf8
+    }
+
+    /// Bytes per code unit.
+    pub(crate) fn unit_len(self) -> usize {
+        match self {
+            Self::Utf8 => 1,
+            Self::Utf16Le | Self::Utf16Be => 2,
+            Self::Utf32Le | Self::Utf32Be => 4,
+        }
+    }
+
+    fn unit(self, bytes: &[u8]) -> u32 {
+        match self {
+            Self::Utf8 => u32::from(bytes[0]),
+            Self::Utf16Le => u32::from(u16::from_le_bytes([bytes[0], bytes[1]])),
+            Self::Utf16Be => u32::from(u16::from_be_bytes([bytes[0], bytes[1]])),
+            Self::Utf32Le => u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+            Self::Utf32Be => u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]),
+        }
+    }
+}
+
+/// Guess the encoding of a stream from its first bytes, returning it along with
+/// the length of its byte order mark.
+///
+/// Without a BOM, UTF-16 and UTF-32 are recognised by the zero bytes that
+/// mostly-ASCII text has in them, so text that is largely outside the Basic
+/// Latin block is taken for UTF-8.
+pub fn detect(sample: &[u8]) -> (Encoding, usize) {
+    // UTF-32LE's BOM starts with UTF-16LE's, so it is checked first
+    const BOMS: [(&[u8], Encoding); 5] = [
+        (b"\xEF\xBB\xBF", Encoding::Utf8),
+        (b"\xFF\xFE\0\0", Encoding::Utf32Le),
+        (b"\0\0\xFE\xFF", Encoding::Utf32Be),
+        (b"\xFF\xFE", Encoding::Utf16Le),
+        (b"\xFE\xFF", Encoding::Utf16Be),
+    ];
+    for (bom, encoding) in BOMS {
+        if sample.starts_with(bom) {
+            return (encoding, bom.len());
+        }
+    }
+    (guess(&sample[..sample.len().min(SAMPLE_LEN)]), 0)
+}
+
+/// Look at the share of zero bytes in each position of the code units.
+fn guess(sample: &[u8]) -> Encoding {
+    let units = sample.len() / 4;
+    if units == 0 {
+        return Encoding::Utf8;
+    }
+    let mut zeros = [0; 4];
+    for (i, &byte) in sample[..units * 4].iter().enumerate() {
+        zeros[i % 4] += usize::from(byte == 0);
+    }
+    // Whether at least 90%, or less than 10%, of `of` bytes are zero
+    let mostly = |zeros: usize, of: usize| zeros * 10 >= of * 9;
+    let rarely = |zeros: usize, of: usize| zeros * 10 < of;
+
+    let high = [zeros[1], zeros[2], zeros[3]];
+    let (even, odd) = (zeros[0] + zeros[2], zeros[1] + zeros[3]);
+    if rarely(zeros[0], units) && high.iter().all(|&z| mostly(z, units)) {
+        Encoding::Utf32Le
+    } else if zeros[..3].iter().all(|&z| mostly(z, units)) && rarely(zeros[3], units) {
+        Encoding::Utf32Be
+    } else if rarely(even, 2 * units) && mostly(odd, 2 * units) {
+        Encoding::Utf16Le
+    } else if mostly(even, 2 * units) && rarely(odd, 2 * units) {
+        Encoding::Utf16Be
+    } else {
+        Encoding::Utf8
+    }
+}
+
+/// Decodes a UTF-16 or UTF-32 stream to UTF-8 as it is fed, replacing invalid
+/// code units with U+FFFD.
+pub(crate) struct Transcoder {
+    encoding: Encoding,
+    /// Bytes of an incomplete code unit or surrogate pair left from the last feed.
+    carry: Vec<u8>,
+    utf8: String,
+}
+
+impl Transcoder {
+    pub(crate) fn new(encoding: Encoding) -> Self {
+        Self {
+            encoding,
+            carry: Vec::new(),
+            utf8: String::new(),
+        }
+    }
+
+    /// Decode the complete characters of `carry` followed by `bytes`.
+    pub(crate) fn feed(&mut self, bytes: &[u8]) -> &[u8] {
+        self.carry.extend_from_slice(bytes);
+        let unit_len = self.encoding.unit_len();
+        let mut end = self.carry.len() - self.carry.len() % unit_len;
+        if unit_len == 2 && end >= 2 {
+            let last = self.encoding.unit(&self.carry[end - 2..end]);
+            if (0xD800..0xDC00).contains(&last) {
+                end -= 2;
+            }
+        }
+        self.decode(end);
+        self.carry.drain(..end);
+        self.utf8.as_bytes()
+    }
+
+    /// Decode whatever is left at the end of the stream.
+    pub(crate) fn finish(&mut self) -> &[u8] {
+        let end = self.carry.len() - self.carry.len() % self.encoding.unit_len();
+        self.decode(end);
+        if end < self.carry.len() {
+            self.utf8.push(char::REPLACEMENT_CHARACTER);
+        }
+        self.carry.clear();
+        self.utf8.as_bytes()
+    }
+
+    fn decode(&mut self, end: usize) {
+        self.utf8.clear();
+        let encoding = self.encoding;
+        let unit_len = encoding.unit_len();
+        let units = self.carry[..end]
+            .chunks_exact(unit_len)
+            .map(|unit| encoding.unit(unit));
+        if unit_len == 2 {
+            #[allow(clippy::cast_possible_truncation)]
+            let units = units.map(|unit| unit as u16);
+            self.utf8.extend(
+                char::decode_utf16(units).map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER)),
+            );
+        } else {
+            self.utf8.extend(
+                units.map(|unit| char::from_u32(unit).unwrap_or(char::REPLACEMENT_CHARACTER)),
+            );
+        }
+    }
+}
@@ -0,0 +1,185 @@
+use crate::syntax::{self, Syntax};
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Language {
+    pub name: &'static str,
+    /// Extensions without the leading dot; the first one is canonical.
+    pub extensions: &'static [&'static str],
+    pub syntax: &'static Syntax,
+}
+
+pub static LANGUAGES: &[Language] = &[
+    Language {
+        name: "C",
+        extensions: &["c", "h"],
+        syntax: &syntax::C,
+    },
+    Language {
+        name: "C++",
+        extensions: &["cpp", "cc", "cxx", "hpp", "hh", "hxx"],
+        syntax: &syntax::C,
+    },
+    Language {
+        name: "C#",
+        extensions: &["cs"],
+        syntax: &syntax::CSHARP,
+    },
+    Language {
+        name: "CSS",
+        extensions: &["css"],
+        syntax: &syntax::CSS,
+    },
+    Language {
+        name: "CSV",
+        extensions: &["csv"],
+        syntax: &syntax::PLAIN,
+    },
+    Language {
+        name: "Dart",
+        extensions: &["dart"],
+        syntax: &syntax::DART,
+    },
+    Language {
+        name: "Elixir",
+        extensions: &["ex", "exs"],
+        syntax: &syntax::ELIXIR,
+    },
+    Language {
+        name: "Go",
+        extensions: &["go"],
+        syntax: &syntax::GO,
+    },
+    Language {
+        name: "Haskell",
+        extensions: &["hs"],
+        syntax: &syntax::HASKELL,
+    },
+    Language {
+        name: "HTML",
+        extensions: &["html", "htm"],
+        syntax: &syntax::XML,
+    },
+    Language {
+        name: "Java",
+        extensions: &["java"],
+        syntax: &syntax::JAVA,
+    },
+    Language {
+        name: "JavaScript",
+        extensions: &["js", "mjs", "cjs", "jsx"],
+        syntax: &syntax::JAVASCRIPT,
+    },
+    Language {
+        name: "JSON",
+        extensions: &["json"],
+        syntax: &syntax::PLAIN,
+    },
+    Language {
+        name: "Kotlin",
+        extensions: &["kt", "kts"],
+        syntax: &syntax::KOTLIN,
+    },
+    Language {
+        name: "Lua",
+        extensions: &["lua"],
+        syntax: &syntax::LUA,
+    },
+    Language {
+        name: "Markdown",
+        extensions: &["md", "markdown"],
+        syntax: &syntax::XML,
+    },
+    Language {
+        name: "PHP",
+        extensions: &["php"],
+        syntax: &syntax::PHP,
+    },
+    Language {
+        name: "Python",
+        extensions: &["py", "pyi"],
+        syntax: &syntax::PYTHON,
+    },
+    Language {
+        name: "Ruby",
+        extensions: &["rb"],
+        syntax: &syntax::RUBY,
+    },
+    Language {
+        name: "Rust",
+        extensions: &["rs"],
+        syntax: &syntax::RUST,
+    },
+    Language {
+        name: "Scala",
+        extensions: &["scala"],
+        syntax: &syntax::SCALA,
+    },
+    Language {
+        name: "Shell",
+        extensions: &["sh", "bash", "zsh"],
+        syntax: &syntax::SHELL,
+    },
+    Language {
+        name: "SQL",
+        extensions: &["sql"],
+        syntax: &syntax::SQL,
+    },
+    Language {
+        name: "Svelte",
+        extensions: &["svelte"],
+        syntax: &syntax::XML,
+    },
+    Language {
+        name: "Swift",
+        extensions: &["swift"],
+        syntax: &syntax::SWIFT,
+    },
+    Language {
+        name: "TOML",
+        extensions: &["toml"],
+        syntax: &syntax::TOML,
+    },
+    Language {
+        name: "TypeScript",
+        extensions: &["ts", "mts", "cts", "tsx"],
+        syntax: &syntax::JAVASCRIPT,
+    },
+    Language {
+        name: "Vue",
+        extensions: &["vue"],
+        syntax: &syntax::XML,
+    },
+    Language {
+        name: "XML",
+        extensions: &["xml"],
+        syntax: &syntax::XML,
+    },
+    Language {
+        name: "YAML",
+        extensions: &["yaml", "yml"],
+        syntax: &syntax::YAML,
+    },
+];
+
+impl Language {
+    /// Look up a language by name or by one of its extensions, ignoring case.
+    pub fn from_name(name: &str) -> Option<&'static Self> {
+        LANGUAGES.iter().find(|lang| {
+            lang.name.eq_ignore_ascii_case(name)
+                || lang
+                    .extensions
+                    .iter()
+                    .any(|ext| ext.eq_ignore_ascii_case(name))
+        })
+    }
+
+    pub fn from_extension(extension: &str) -> Option<&'static Self> {
+        LANGUAGES
+            .iter()
+            .find(|lang| lang.extensions.contains(&extension))
+    }
+
+    pub fn extension(&self) -> &'static str {
+        self.extensions[0]
+    }
+}
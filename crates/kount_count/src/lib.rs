@@ -1,6 +1,22 @@
+pub mod archive;
 pub mod counter;
+pub mod decompress;
+pub mod dedup;
+pub mod detect;
+pub mod embedded;
+pub mod encoding;
+pub mod language;
+pub mod markers;
+pub mod notebook;
+pub mod stats;
+pub mod syntax;
+pub mod test_code;
 pub mod types;
 pub mod walker;
+pub mod watch;
 
-pub use types::{CountConfig, CountResult, SortOrder};
+pub use counter::Metrics;
+pub use language::Language;
+pub use types::{CountConfig, CountResult, SortOrder, TotalsScope};
 pub use walker::count;
+pub use watch::watch;
@@ -0,0 +1,84 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::syntax::Lexer;
+
+/// The markers `--markers` looks for unless it is given others.
+pub const DEFAULT_MARKERS: &[&str] = &["TODO", "FIXME", "HACK", "XXX"];
+
+/// Occurrences of each marker, including the ones that weren't found.
+pub type MarkerCounts = BTreeMap<String, u64>;
+
+/// A marker found in a comment, on the 1-based `line`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct MarkerLocation {
+    pub marker: String,
+    /// The 1-based cell of a notebook the marker is in, which `line` is then
+    /// counted from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cell: Option<u64>,
+    pub line: u64,
+}
+
+/// Call `found` with each of `markers` that occurs as a whole word in
+/// `comment`, so that `TODO:` and `TODO(alice)` count but `TODOS` doesn't.
+pub(crate) fn find_markers(comment: &[u8], markers: &[String], mut found: impl FnMut(&str)) {
+    let is_word = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_';
+    let mut i = 0;
+    while i < comment.len() {
+        if i > 0 && is_word(comment[i - 1]) {
+            i += 1;
+            continue;
+        }
+        let marker = markers.iter().find(|marker| {
+            comment[i..].starts_with(marker.as_bytes())
+                && comment.get(i + marker.len()).is_none_or(|&b| !is_word(b))
+        });
+        match marker {
+            Some(marker) => {
+                found(marker);
+                i += marker.len();
+            }
+            None => i += 1,
+        }
+    }
+}
+
+/// Record the `markers` in the comments `lexer` found on `line`, which is
+/// line `number` of its file.
+pub(crate) fn find_line_markers(
+    line: &[u8],
+    number: u64,
+    lexer: &Lexer,
+    markers: &[String],
+    found: &mut Vec<MarkerLocation>,
+) {
+    for comment in lexer.comments() {
+        find_markers(&line[comment.clone()], markers, |marker| {
+            found.push(MarkerLocation {
+                marker: marker.to_string(),
+                cell: None,
+                line: number,
+            });
+        });
+    }
+}
+
+/// Count the markers of `locations`, starting each of `markers` at zero.
+pub(crate) fn count_markers(markers: &[String], locations: &[MarkerLocation]) -> MarkerCounts {
+    let mut counts: MarkerCounts = markers.iter().map(|marker| (marker.clone(), 0)).collect();
+    for location in locations {
+        *counts.entry(location.marker.clone()).or_default() += 1;
+    }
+    counts
+}
+
+/// Add the counts of `other` to `total`.
+pub(crate) fn add_markers(total: &mut Option<MarkerCounts>, other: Option<&MarkerCounts>) {
+    if let (Some(total), Some(other)) = (total, other) {
+        for (marker, count) in other {
+            *total.entry(marker.clone()).or_default() += count;
+        }
+    }
+}
@@ -0,0 +1,167 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::counter::{count_reader, Counts, Metrics};
+use crate::embedded::{BlockLines, EmbeddedCount};
+use crate::language::Language;
+use crate::markers::{count_markers, MarkerLocation};
+use crate::syntax::LineKinds;
+
+/// The parts of an nbformat 4 notebook that are counted; outputs and the rest
+/// of the metadata are skipped.
+#[derive(Deserialize)]
+struct Notebook {
+    cells: Vec<Cell>,
+    #[serde(default)]
+    metadata: Metadata,
+}
+
+#[derive(Deserialize)]
+struct Cell {
+    cell_type: String,
+    #[serde(default)]
+    source: Source,
+}
+
+/// A cell's source, which notebooks store either whole or split into lines.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Source {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl Source {
+    fn text(&self) -> String {
+        match self {
+            Self::Text(text) => text.clone(),
+            Self::Lines(lines) => lines.concat(),
+        }
+    }
+}
+
+#[derive(Deserialize, Default)]
+struct Metadata {
+    kernelspec: Option<KernelSpec>,
+    language_info: Option<LanguageInfo>,
+}
+
+#[derive(Deserialize)]
+struct KernelSpec {
+    language: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct LanguageInfo {
+    name: Option<String>,
+}
+
+impl Metadata {
+    /// The language of the code cells, as named by the kernel.
+    fn language(self) -> Option<String> {
+        let (language, kernel) = self
+            .kernelspec
+            .map_or((None, None), |spec| (spec.language, spec.name));
+        language
+            .or(self.language_info.and_then(|info| info.name))
+            .or(kernel)
+    }
+}
+
+pub fn is_notebook(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "ipynb")
+}
+
+/// Count the sources of the code and Markdown cells of a Jupyter notebook as
+/// if they were the whole file, along with their lines by language: code
+/// cells in the kernel's language, and Markdown cells. Raw cells are skipped.
+/// Bytes are those of the whole file, as `wc -c` counts them.
+pub fn count_notebook(path: &Path, metrics: &Metrics) -> io::Result<Counts> {
+    let content = fs::read(path)?;
+    let notebook: Notebook = serde_json::from_slice(&content)?;
+    let kernel = notebook.metadata.language();
+    let language = kernel.as_deref().and_then(Language::from_name);
+    let name = match (language, kernel) {
+        (Some(language), _) => language.name.to_string(),
+        (None, Some(kernel)) => kernel,
+        (None, None) => "code".to_string(),
+    };
+
+    let mut code = EmbeddedCount::new(name);
+    let mut markdown = EmbeddedCount::new("Markdown".to_string());
+    let mut sources = Vec::new();
+    let mut found = Vec::new();
+    for (index, cell) in (1..).zip(&notebook.cells) {
+        let (count, language) = match cell.cell_type.as_str() {
+            "code" => (&mut code, language),
+            "markdown" => (&mut markdown, Language::from_name("markdown")),
+            _ => continue,
+        };
+        let source = cell.source.text();
+        if source.is_empty() {
+            continue;
+        }
+        // Markers are located by their cell and their line in it
+        let mut block = BlockLines::new(language, metrics);
+        for (number, line) in (1..).zip(source.lines()) {
+            block.add_line(line.as_bytes(), number);
+        }
+        found.extend(
+            count
+                .add_block(block, metrics.classify)
+                .into_iter()
+                .map(|location| MarkerLocation {
+                    cell: Some(index),
+                    ..location
+                }),
+        );
+        sources.extend_from_slice(source.as_bytes());
+        if !source.ends_with('\n') {
+            sources.push(b'\n');
+        }
+    }
+
+    // The cells were lexed with their own syntax above
+    let counts = count_reader(
+        &sources[..],
+        &Metrics {
+            classify: false,
+            markers: None,
+            ..metrics.clone()
+        },
+    )?;
+    let cells = [code, markdown];
+    let sum = |kind: fn(&EmbeddedCount) -> Option<u64>| cells.iter().filter_map(kind).sum();
+    let line_kinds = metrics.classify.then(|| LineKinds {
+        code: sum(|c| c.code),
+        comments: sum(|c| c.comments),
+        docs: sum(|c| c.doc_lines),
+        blanks: sum(|c| c.blanks),
+    });
+    let embedded = cells.into_iter().filter(|c| c.blocks > 0).collect();
+    Ok(Counts {
+        bytes: metrics.bytes.then_some(content.len() as u64),
+        markers: metrics
+            .markers
+            .as_ref()
+            .map(|markers| count_markers(markers, &found)),
+        marker_locations: if metrics.marker_locations {
+            found
+        } else {
+            Vec::new()
+        },
+        line_kinds,
+        embedded,
+        ..counts
+    })
+}
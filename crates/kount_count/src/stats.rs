@@ -0,0 +1,101 @@
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::types::FileCount;
+
+/// How lines are distributed over a set of files.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct Distribution {
+    pub mean: f64,
+    /// Percentiles use the nearest-rank method, so they are always the line
+    /// count of an actual file.
+    pub median: u64,
+    pub p90: u64,
+    pub p99: u64,
+    pub max: u64,
+    /// The file with `max` lines.
+    pub largest: Option<PathBuf>,
+}
+
+impl Distribution {
+    pub fn new<'a>(files: impl IntoIterator<Item = &'a FileCount>) -> Self {
+        let files: Vec<&FileCount> = files.into_iter().collect();
+        let Some(largest) = files.iter().max_by_key(|f| f.lines) else {
+            return Self::default();
+        };
+
+        let mut lines: Vec<u64> = files.iter().map(|f| f.lines).collect();
+        lines.sort_unstable();
+        let total: u64 = lines.iter().sum();
+
+        #[allow(clippy::cast_precision_loss)]
+        let mean = total as f64 / lines.len() as f64;
+        Self {
+            mean,
+            median: percentile(&lines, 50),
+            p90: percentile(&lines, 90),
+            p99: percentile(&lines, 99),
+            max: largest.lines,
+            largest: Some(largest.path.clone()),
+        }
+    }
+}
+
+/// The nearest-rank percentile of sorted, non-empty `lines`.
+fn percentile(lines: &[u64], percent: usize) -> u64 {
+    let rank = (lines.len() * percent).div_ceil(100).max(1);
+    lines[rank - 1]
+}
+
+/// Files whose line count lies within `min..=max`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Bucket {
+    pub min: u64,
+    pub max: u64,
+    pub files: u64,
+}
+
+/// Count files per order of magnitude of their line count: 0, 1-9, 10-99 and
+/// so on, from the smallest to the largest non-empty bucket.
+pub fn histogram<'a>(files: impl IntoIterator<Item = &'a FileCount>) -> Vec<Bucket> {
+    let mut counts: Vec<u64> = Vec::new();
+    for file in files {
+        let index = magnitude(file.lines) as usize;
+        if counts.len() <= index {
+            counts.resize(index + 1, 0);
+        }
+        counts[index] += 1;
+    }
+
+    let first = counts.iter().position(|&n| n > 0).unwrap_or(counts.len());
+    counts
+        .into_iter()
+        .zip(0..)
+        .skip(first)
+        .map(|(files, digits)| {
+            let (min, max) = bucket_bounds(digits);
+            Bucket { min, max, files }
+        })
+        .collect()
+}
+
+/// 0 for an empty file, otherwise the number of decimal digits.
+fn magnitude(lines: u64) -> u32 {
+    if lines == 0 {
+        0
+    } else {
+        lines.ilog10() + 1
+    }
+}
+
+fn bucket_bounds(digits: u32) -> (u64, u64) {
+    if digits == 0 {
+        (0, 0)
+    } else {
+        (
+            10u64.pow(digits - 1),
+            10u64.checked_pow(digits).map_or(u64::MAX, |n| n - 1),
+        )
+    }
+}
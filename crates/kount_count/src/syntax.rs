@@ -0,0 +1,574 @@
+use std::ops::Range;
+
+/// How a language writes comments and string literals, which is all the
+/// lexer needs to tell code lines from comment lines.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Syntax {
+    pub line_comments: &'static [&'static str],
+    /// Opening and closing delimiters, checked before `line_comments` so that
+    /// Lua's `--[[` wins over `--`.
+    pub block_comments: &'static [(&'static str, &'static str)],
+    /// Whether block comments nest, as in Rust and Swift.
+    pub nested_comments: bool,
+    /// Checked in order, so longer delimiters must come first.
+    pub strings: &'static [Quote],
+    pub raw_strings: RawStrings,
+    /// Whether `'` starts a one-character literal rather than a string, so that
+    /// Rust lifetimes and Scala symbols aren't taken for quotes.
+    pub char_literals: bool,
+    /// Comment openers that make a comment documentation, unless they are
+    /// followed by their own last character again, as in `////`.
+    pub doc_comments: &'static [&'static str],
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct Quote {
+    pub open: &'static str,
+    pub close: &'static str,
+    /// Whether a backslash escapes the next character.
+    pub escapes: bool,
+    /// Whether the literal can span lines.
+    pub multiline: bool,
+    /// Whether the literal is documentation when it starts a line, as Python
+    /// docstrings do.
+    pub doc: bool,
+}
+
+/// Raw string literals whose delimiter is only known once they are opened.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RawStrings {
+    None,
+    /// `r"…"`, `r#"…"#` and so on.
+    Rust,
+    /// `#"…"#` and `#"""…"""#`, with any number of `#`.
+    Swift,
+}
+
+const fn quote(open: &'static str, close: &'static str, escapes: bool, multiline: bool) -> Quote {
+    Quote {
+        open,
+        close,
+        escapes,
+        multiline,
+        doc: false,
+    }
+}
+
+const DOUBLE: Quote = quote("\"", "\"", true, false);
+const SINGLE: Quote = quote("'", "'", true, false);
+const TRIPLE_DOUBLE: Quote = quote("\"\"\"", "\"\"\"", true, true);
+const TRIPLE_SINGLE: Quote = quote("'''", "'''", true, true);
+const C_BLOCK: &[(&str, &str)] = &[("/*", "*/")];
+/// Doxygen's doc comments, which Rust and Javadoc use subsets of.
+const C_DOCS: &[&str] = &["///", "//!", "/**", "/*!"];
+const XML_BLOCK: &[(&str, &str)] = &[("<!--", "-->")];
+
+/// Languages without comments, such as JSON. Every line with content is code.
+pub const PLAIN: Syntax = Syntax {
+    line_comments: &[],
+    block_comments: &[],
+    nested_comments: false,
+    strings: &[],
+    raw_strings: RawStrings::None,
+    char_literals: false,
+    doc_comments: &[],
+};
+
+pub const C: Syntax = Syntax {
+    line_comments: &["//"],
+    block_comments: C_BLOCK,
+    strings: &[DOUBLE, SINGLE],
+    doc_comments: C_DOCS,
+    ..PLAIN
+};
+
+pub const CSHARP: Syntax = Syntax {
+    // Verbatim strings double their quotes, which reads as two strings here
+    strings: &[quote("@\"", "\"", false, true), DOUBLE, SINGLE],
+    ..C
+};
+
+pub const CSS: Syntax = Syntax {
+    line_comments: &[],
+    doc_comments: &[],
+    ..C
+};
+
+pub const DART: Syntax = Syntax {
+    nested_comments: true,
+    strings: &[TRIPLE_DOUBLE, TRIPLE_SINGLE, DOUBLE, SINGLE],
+    ..C
+};
+
+pub const GO: Syntax = Syntax {
+    strings: &[DOUBLE, SINGLE, quote("`", "`", false, true)],
+    // Go documents code with plain comments
+    doc_comments: &[],
+    ..C
+};
+
+pub const JAVA: Syntax = Syntax {
+    strings: &[TRIPLE_DOUBLE, DOUBLE, SINGLE],
+    ..C
+};
+
+pub const JAVASCRIPT: Syntax = Syntax {
+    strings: &[DOUBLE, SINGLE, quote("`", "`", true, true)],
+    ..C
+};
+
+pub const KOTLIN: Syntax = Syntax {
+    nested_comments: true,
+    strings: &[quote("\"\"\"", "\"\"\"", false, true), DOUBLE, SINGLE],
+    ..C
+};
+
+pub const PHP: Syntax = Syntax {
+    line_comments: &["//", "#"],
+    ..C
+};
+
+pub const RUST: Syntax = Syntax {
+    nested_comments: true,
+    strings: &[quote("\"", "\"", true, true)],
+    raw_strings: RawStrings::Rust,
+    char_literals: true,
+    ..C
+};
+
+pub const SCALA: Syntax = Syntax {
+    nested_comments: true,
+    strings: &[quote("\"\"\"", "\"\"\"", false, true), DOUBLE],
+    char_literals: true,
+    ..C
+};
+
+pub const SWIFT: Syntax = Syntax {
+    nested_comments: true,
+    strings: &[TRIPLE_DOUBLE, DOUBLE],
+    raw_strings: RawStrings::Swift,
+    ..C
+};
+
+pub const ELIXIR: Syntax = Syntax {
+    line_comments: &["#"],
+    strings: &[TRIPLE_DOUBLE, TRIPLE_SINGLE, DOUBLE, SINGLE],
+    ..PLAIN
+};
+
+pub const HASKELL: Syntax = Syntax {
+    line_comments: &["--"],
+    block_comments: &[("{-", "-}")],
+    nested_comments: true,
+    strings: &[DOUBLE],
+    doc_comments: &["-- |", "{-|"],
+    ..PLAIN
+};
+
+pub const LUA: Syntax = Syntax {
+    line_comments: &["--"],
+    block_comments: &[("--[[", "]]")],
+    strings: &[quote("[[", "]]", false, true), DOUBLE, SINGLE],
+    doc_comments: &["---"],
+    ..PLAIN
+};
+
+pub const PYTHON: Syntax = Syntax {
+    line_comments: &["#"],
+    strings: &[
+        Quote {
+            doc: true,
+            ..TRIPLE_DOUBLE
+        },
+        Quote {
+            doc: true,
+            ..TRIPLE_SINGLE
+        },
+        DOUBLE,
+        SINGLE,
+    ],
+    ..PLAIN
+};
+
+pub const RUBY: Syntax = Syntax {
+    line_comments: &["#"],
+    block_comments: &[("=begin", "=end")],
+    strings: &[DOUBLE, SINGLE],
+    ..PLAIN
+};
+
+pub const SHELL: Syntax = Syntax {
+    line_comments: &["#"],
+    strings: &[quote("\"", "\"", true, true), quote("'", "'", false, true)],
+    ..PLAIN
+};
+
+pub const SQL: Syntax = Syntax {
+    line_comments: &["--"],
+    block_comments: C_BLOCK,
+    // Quotes are escaped by doubling them, which reads as two strings here
+    strings: &[quote("'", "'", false, true), DOUBLE],
+    ..PLAIN
+};
+
+pub const TOML: Syntax = Syntax {
+    line_comments: &["#"],
+    strings: &[
+        TRIPLE_DOUBLE,
+        quote("'''", "'''", false, true),
+        DOUBLE,
+        quote("'", "'", false, false),
+    ],
+    ..PLAIN
+};
+
+pub const XML: Syntax = Syntax {
+    block_comments: XML_BLOCK,
+    ..PLAIN
+};
+
+pub const YAML: Syntax = Syntax {
+    line_comments: &["#"],
+    strings: &[DOUBLE, quote("'", "'", false, false)],
+    ..PLAIN
+};
+
+/// Lines of code, comments and blank lines, as told apart by `--classify`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LineKinds {
+    pub code: u64,
+    pub comments: u64,
+    /// Comment lines that are documentation.
+    pub docs: u64,
+    pub blanks: u64,
+}
+
+impl LineKinds {
+    pub fn add(&mut self, kind: LineKind) {
+        let count = match kind {
+            LineKind::Code => &mut self.code,
+            LineKind::Comment => &mut self.comments,
+            LineKind::Doc => {
+                self.docs += 1;
+                &mut self.comments
+            }
+            LineKind::Blank => &mut self.blanks,
+        };
+        *count += 1;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineKind {
+    Code,
+    Comment,
+    /// A comment line that is documentation.
+    Doc,
+    Blank,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum State {
+    Code,
+    /// Inside the block comment at `index` of `block_comments`.
+    Comment {
+        index: usize,
+        depth: u32,
+        doc: bool,
+    },
+    /// Inside the string at `index` of `strings`, which is a docstring if `doc`.
+    String {
+        index: usize,
+        doc: bool,
+    },
+    /// Inside a raw string, closed by `quotes` quotes followed by `hashes` `#`.
+    Raw {
+        quotes: usize,
+        hashes: usize,
+    },
+}
+
+/// Classifies the lines of one file in order, carrying open comments and
+/// strings from one line to the next.
+///
+/// A line is code if anything but a comment starts on it or it is part of a
+/// string literal, a comment if it holds or is inside a comment, and blank
+/// otherwise. Comment lines are documentation if any of their comments is.
+#[derive(Debug, Clone)]
+pub struct Lexer {
+    syntax: &'static Syntax,
+    state: State,
+    /// The comments and docstrings of the line last classified.
+    comments: Vec<Range<usize>>,
+    /// Braces opened and not yet closed, outside comments and literals.
+    depth: u32,
+}
+
+impl Lexer {
+    pub fn new(syntax: &'static Syntax) -> Self {
+        Self {
+            syntax,
+            state: State::Code,
+            comments: Vec::new(),
+            depth: 0,
+        }
+    }
+
+    /// The byte ranges of the comments and docstrings in the line last
+    /// classified, including their delimiters.
+    pub fn comments(&self) -> &[Range<usize>] {
+        &self.comments
+    }
+
+    /// How deeply the code is nested in braces after the line last classified.
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Classify `line`, which doesn't include its newline.
+    pub fn classify(&mut self, line: &[u8]) -> LineKind {
+        self.comments.clear();
+        if line.iter().all(u8::is_ascii_whitespace) {
+            // Blank lines inside literals are part of them
+            return match self.state {
+                State::String { doc: true, .. } => LineKind::Doc,
+                State::String { .. } | State::Raw { .. } => LineKind::Code,
+                State::Code | State::Comment { .. } => LineKind::Blank,
+            };
+        }
+
+        let syntax = self.syntax;
+        let (mut code, mut comment, mut doc) = match self.state {
+            State::Code => (false, false, false),
+            State::Comment { doc, .. } | State::String { doc, .. } if doc => (false, true, true),
+            State::Comment { .. } => (false, true, false),
+            State::String { .. } | State::Raw { .. } => (true, false, false),
+        };
+        // Where the comment or docstring the line is in started
+        let mut start = comment.then_some(0);
+        let mut i = 0;
+        while i < line.len() {
+            let rest = &line[i..];
+            match self.state {
+                State::Code if rest[0].is_ascii_whitespace() => i += 1,
+                State::Code => {
+                    let block = syntax
+                        .block_comments
+                        .iter()
+                        .position(|(open, _)| rest.starts_with(open.as_bytes()));
+                    if let Some(index) = block {
+                        let (open, close) = syntax.block_comments[index];
+                        // `/**/` is empty rather than documentation
+                        let is_doc =
+                            self.is_doc(rest) && !rest[open.len()..].starts_with(close.as_bytes());
+                        self.state = State::Comment {
+                            index,
+                            depth: 1,
+                            doc: is_doc,
+                        };
+                        comment = true;
+                        doc |= is_doc;
+                        start = Some(i);
+                        i += open.len();
+                    } else if syntax
+                        .line_comments
+                        .iter()
+                        .any(|open| rest.starts_with(open.as_bytes()))
+                    {
+                        comment = true;
+                        doc |= self.is_doc(rest);
+                        start = Some(i);
+                        break;
+                    } else {
+                        let literal = i;
+                        i += self.start_literal(line, i, !code && !comment);
+                        if i == literal + 1 && self.state == State::Code {
+                            match rest[0] {
+                                b'{' => self.depth += 1,
+                                b'}' => self.depth = self.depth.saturating_sub(1),
+                                _ => {}
+                            }
+                        }
+                        if let State::String { doc: true, .. } = self.state {
+                            comment = true;
+                            doc = true;
+                            start = Some(literal);
+                        } else {
+                            code = true;
+                        }
+                    }
+                }
+                State::Comment { index, depth, doc } => {
+                    let (open, close) = syntax.block_comments[index];
+                    if rest.starts_with(close.as_bytes()) {
+                        self.state = match depth {
+                            1 => State::Code,
+                            _ => State::Comment {
+                                index,
+                                depth: depth - 1,
+                                doc,
+                            },
+                        };
+                        i += close.len();
+                        if depth == 1 {
+                            self.close_comment(&mut start, i);
+                        }
+                    } else if syntax.nested_comments && rest.starts_with(open.as_bytes()) {
+                        self.state = State::Comment {
+                            index,
+                            depth: depth + 1,
+                            doc,
+                        };
+                        i += open.len();
+                    } else {
+                        i += 1;
+                    }
+                }
+                State::String { index, doc } => {
+                    let quote = &syntax.strings[index];
+                    if quote.escapes && rest[0] == b'\\' {
+                        i += 2;
+                    } else if rest.starts_with(quote.close.as_bytes()) {
+                        self.state = State::Code;
+                        i += quote.close.len();
+                        if doc {
+                            self.close_comment(&mut start, i);
+                        }
+                    } else {
+                        i += 1;
+                    }
+                }
+                State::Raw { quotes, hashes } => {
+                    if closes_raw(rest, quotes, hashes) {
+                        self.state = State::Code;
+                        i += quotes + hashes;
+                    } else {
+                        i += 1;
+                    }
+                }
+            }
+        }
+
+        if let State::String { index, .. } = self.state {
+            if !syntax.strings[index].multiline {
+                self.state = State::Code;
+            }
+        }
+        self.close_comment(&mut start, line.len());
+
+        if code {
+            LineKind::Code
+        } else if doc {
+            LineKind::Doc
+        } else if comment {
+            LineKind::Comment
+        } else {
+            LineKind::Blank
+        }
+    }
+
+    /// Record the comment from `start` to `end`, if one is open.
+    fn close_comment(&mut self, start: &mut Option<usize>, end: usize) {
+        if let Some(start) = start.take() {
+            self.comments.push(start..end);
+        }
+    }
+
+    /// Whether the comment starting `rest` is documentation.
+    fn is_doc(&self, rest: &[u8]) -> bool {
+        self.syntax.doc_comments.iter().any(|prefix| {
+            rest.starts_with(prefix.as_bytes())
+                && rest.get(prefix.len()) != prefix.as_bytes().last()
+        })
+    }
+
+    /// Enter the literal starting at `line[i]` if there is one, returning how
+    /// many bytes to skip. Only a literal that `starts_line` can be a docstring.
+    fn start_literal(&mut self, line: &[u8], i: usize, starts_line: bool) -> usize {
+        let rest = &line[i..];
+        if let Some((len, state)) = self.raw_string(line, i) {
+            self.state = state;
+            return len;
+        }
+        if self.syntax.char_literals && rest[0] == b'\'' {
+            return char_literal_len(rest).unwrap_or(1);
+        }
+        let string = self
+            .syntax
+            .strings
+            .iter()
+            .position(|quote| rest.starts_with(quote.open.as_bytes()));
+        match string {
+            Some(index) => {
+                let quote = &self.syntax.strings[index];
+                self.state = State::String {
+                    index,
+                    doc: quote.doc && starts_line,
+                };
+                quote.open.len()
+            }
+            None => 1,
+        }
+    }
+
+    /// The length of the opening delimiter of a raw string at `line[i]`, and
+    /// the state inside it.
+    fn raw_string(&self, line: &[u8], i: usize) -> Option<(usize, State)> {
+        let rest = &line[i..];
+        match self.syntax.raw_strings {
+            RawStrings::None => None,
+            RawStrings::Rust => {
+                // `r` may follow a `b` or `c` prefix, but not other identifier characters
+                let prefix = match i.checked_sub(1).map(|p| line[p]) {
+                    Some(b'b' | b'c') => i.checked_sub(2).map(|p| line[p]),
+                    before => before,
+                };
+                if rest[0] != b'r' || prefix.is_some_and(is_identifier) {
+                    return None;
+                }
+                let hashes = rest[1..].iter().take_while(|&&b| b == b'#').count();
+                (rest.get(1 + hashes) == Some(&b'"'))
+                    .then_some((hashes + 2, State::Raw { quotes: 1, hashes }))
+            }
+            RawStrings::Swift => {
+                let hashes = rest.iter().take_while(|&&b| b == b'#').count();
+                if hashes == 0 || rest.get(hashes) != Some(&b'"') {
+                    return None;
+                }
+                let quotes = if rest[hashes..].starts_with(b"\"\"\"") {
+                    3
+                } else {
+                    1
+                };
+                Some((hashes + quotes, State::Raw { quotes, hashes }))
+            }
+        }
+    }
+}
+
+fn closes_raw(rest: &[u8], quotes: usize, hashes: usize) -> bool {
+    rest.len() >= quotes + hashes
+        && rest[..quotes].iter().all(|&b| b == b'"')
+        && rest[quotes..quotes + hashes].iter().all(|&b| b == b'#')
+}
+
+/// The length of a character literal such as `'a'` or `'\n'` at the start of
+/// `rest`, or `None` if the quote starts a lifetime or symbol instead.
+fn char_literal_len(rest: &[u8]) -> Option<usize> {
+    if rest.get(1) == Some(&b'\\') {
+        // Long enough for `'\u{10FFFF}'`
+        let close = rest.iter().skip(3).take(8).position(|&b| b == b'\'')?;
+        return Some(close + 4);
+    }
+    let len = match *rest.get(1)? {
+        b if b < 0x80 => 1,
+        b if b < 0xE0 => 2,
+        b if b < 0xF0 => 3,
+        _ => 4,
+    };
+    (rest.get(1 + len) == Some(&b'\'')).then_some(len + 2)
+}
+
+fn is_identifier(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte >= 0x80
+}
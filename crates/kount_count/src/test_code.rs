@@ -0,0 +1,147 @@
+use std::mem;
+use std::path::{Component, Path};
+
+use crate::syntax::LineKind;
+
+/// Directories whose contents are tests.
+const TEST_DIRS: &[&str] = &["tests", "test", "__tests__", "spec"];
+
+/// File name endings of test files.
+const TEST_SUFFIXES: &[&str] = &[
+    "_test.go",
+    "_test.py",
+    "_test.rb",
+    "_spec.rb",
+    ".spec.ts",
+    ".spec.tsx",
+    ".spec.js",
+    ".spec.jsx",
+    ".test.ts",
+    ".test.tsx",
+    ".test.js",
+    ".test.jsx",
+];
+
+/// Files like `tests/cli.rs`, `server_test.go`, `test_parser.py` or
+/// `app.spec.ts`.
+pub fn is_test_path(path: &Path) -> bool {
+    let in_test_dir = path.parent().is_some_and(|parent| {
+        parent.components().any(|c| match c {
+            Component::Normal(name) => name.to_str().is_some_and(|n| TEST_DIRS.contains(&n)),
+            _ => false,
+        })
+    });
+    let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+        return in_test_dir;
+    };
+    in_test_dir
+        || TEST_SUFFIXES.iter().any(|s| name.ends_with(s))
+        || (name.starts_with("test_") && path.extension().is_some_and(|e| e == "py"))
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Scan {
+    Outside,
+    /// After a `#[cfg(test)]` on line `start`, waiting for the item it applies to.
+    Attribute {
+        start: u64,
+    },
+    /// Inside the module whose attribute is on line `start`, which ends when
+    /// the brace depth is back to `outer` once it has been `opened`.
+    Module {
+        start: u64,
+        outer: u32,
+        opened: bool,
+    },
+}
+
+/// Counts the lines of the `#[cfg(test)]` modules in Rust source, from the
+/// attribute to the closing brace, as the lines are lexed.
+#[derive(Debug, Clone)]
+pub(crate) struct TestModules {
+    scan: Scan,
+    /// The brace depth before the next line.
+    depth: u32,
+    line: u64,
+    lines: u64,
+}
+
+impl TestModules {
+    pub(crate) fn new() -> Self {
+        Self {
+            scan: Scan::Outside,
+            depth: 0,
+            line: 0,
+            lines: 0,
+        }
+    }
+
+    /// Follow the next `line`, lexed as `kind`, after which the lexer is
+    /// `depth` braces deep.
+    pub(crate) fn feed(&mut self, line: &[u8], kind: LineKind, depth: u32) {
+        self.line += 1;
+        let outer = mem::replace(&mut self.depth, depth);
+        let mut rest = line.trim_ascii();
+        if let Scan::Outside = self.scan {
+            let Some(after) = rest.strip_prefix(b"#[cfg(test)]") else {
+                return;
+            };
+            if kind != LineKind::Code {
+                return;
+            }
+            self.scan = Scan::Attribute { start: self.line };
+            rest = after.trim_ascii();
+        }
+        if let Scan::Attribute { start } = self.scan {
+            if rest.is_empty() || rest.starts_with(b"#[") || kind != LineKind::Code {
+                return;
+            }
+            self.scan = if is_inline_module(rest) {
+                Scan::Module {
+                    start,
+                    outer,
+                    opened: false,
+                }
+            } else {
+                Scan::Outside
+            };
+        }
+        if let Scan::Module {
+            start,
+            outer,
+            opened,
+        } = &mut self.scan
+        {
+            *opened |= depth > *outer || rest.contains(&b'{');
+            if *opened && depth <= *outer {
+                self.lines += self.line - *start + 1;
+                self.scan = Scan::Outside;
+            }
+        }
+    }
+
+    /// The lines counted, with a module left open running to the end.
+    pub(crate) fn finish(self) -> u64 {
+        match self.scan {
+            Scan::Module { start, .. } => self.lines + self.line - start + 1,
+            _ => self.lines,
+        }
+    }
+}
+
+/// Whether `line` starts a `mod name { ... }`, rather than `mod name;` or
+/// another item.
+fn is_inline_module(line: &[u8]) -> bool {
+    let mut rest = line;
+    if let Some(after) = rest.strip_prefix(b"pub") {
+        rest = match after.trim_ascii_start().strip_prefix(b"(") {
+            Some(scope) => scope
+                .iter()
+                .position(|&b| b == b')')
+                .map_or(&[][..], |end| &scope[end + 1..]),
+            None => after,
+        }
+        .trim_ascii_start();
+    }
+    rest.starts_with(b"mod ") && !rest.ends_with(b";")
+}
@@ -1,11 +1,122 @@
-use std::path::PathBuf;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 use serde::Serialize;
 
+use crate::counter::{Counts, LineLengths, Metrics};
+use crate::dedup::DuplicateGroup;
+use crate::detect::{is_generated_name, is_minified_name, Excluded};
+use crate::embedded::EmbeddedCount;
+use crate::encoding::Encoding;
+use crate::language::Language;
+use crate::markers::{add_markers, MarkerCounts, MarkerLocation};
+use crate::stats::{Bucket, Distribution};
+
 #[derive(Debug, Clone, Serialize)]
 pub struct FileCount {
     pub path: PathBuf,
     pub lines: u64,
+    /// The extension the file is grouped under in `by_extension`.
+    pub extension: String,
+    /// Whether `lines` counts the uncompressed content of the file.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub decompressed: bool,
+    /// Whether the file looks like the output of a code generator.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub generated: bool,
+    /// Whether the file is third-party code checked into the tree.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub vendored: bool,
+    /// Whether the file looks minified, by its name or its content.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub minified: bool,
+    #[serde(skip_serializing_if = "Encoding::is_utf8")]
+    pub encoding: Encoding,
+    /// Whether the file starts with a byte order mark.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub bom: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chars: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub words: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<u64>,
+    /// Comment lines that are documentation.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc_lines: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blanks: Option<u64>,
+    /// Lines that are test code: all of them in test files, and those of
+    /// `#[cfg(test)]` modules in Rust.
+    #[serde(skip_serializing_if = "is_zero")]
+    pub test_lines: u64,
+    /// Occurrences of each `--markers` word in comments.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markers: Option<MarkerCounts>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub marker_locations: Vec<MarkerLocation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_length: Option<LineLengths>,
+    /// The languages embedded in the file, whose lines are also counted in
+    /// the file's own.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub embedded: Vec<EmbeddedCount>,
+    /// Share of the total lines, between 0 and 1.
+    pub line_fraction: f64,
+    /// The length and hash of content that isn't on disk, such as stdin, for
+    /// `--dedup` to compare.
+    #[serde(skip)]
+    pub(crate) content: Option<(u64, u128)>,
+}
+
+impl FileCount {
+    pub fn new(path: PathBuf, counts: Counts) -> Self {
+        let extension = extension_of(&path);
+        let minified = counts.minified || is_minified_name(&path);
+        let generated = counts.generated || is_generated_name(&path);
+        Self {
+            path,
+            lines: counts.lines,
+            extension,
+            decompressed: false,
+            generated,
+            vendored: false,
+            minified,
+            encoding: counts.encoding,
+            bom: counts.bom,
+            bytes: counts.bytes,
+            chars: counts.chars,
+            words: counts.words,
+            code: counts.line_kinds.map(|k| k.code),
+            comments: counts.line_kinds.map(|k| k.comments),
+            doc_lines: counts.line_kinds.map(|k| k.docs),
+            blanks: counts.line_kinds.map(|k| k.blanks),
+            test_lines: counts.test_lines.min(counts.lines),
+            markers: counts.markers,
+            marker_locations: counts.marker_locations,
+            line_length: counts.line_length,
+            embedded: counts.embedded,
+            line_fraction: 0.0,
+            content: None,
+        }
+    }
+}
+
+#[allow(clippy::trivially_copy_pass_by_ref)]
+fn is_zero(n: &u64) -> bool {
+    *n == 0
+}
+
+pub fn extension_of(path: &Path) -> String {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .unwrap_or("")
+        .to_string()
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -13,6 +124,30 @@ pub struct ExtensionSummary {
     pub extension: String,
     pub file_count: u64,
     pub total_lines: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_chars: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_words: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_code: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_comments: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_doc_lines: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_blanks: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markers: Option<MarkerCounts>,
+    /// Shares of the total lines and files, between 0 and 1.
+    pub line_fraction: f64,
+    pub file_fraction: f64,
+    pub distribution: Distribution,
+    /// Set on the row rolling up the extensions left out by `--top-ext`,
+    /// listing them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rolled_up: Option<Vec<String>>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -20,7 +155,83 @@ pub struct CountResult {
     pub files: Vec<FileCount>,
     pub total_lines: u64,
     pub total_files: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_chars: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_words: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_code: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_comments: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_doc_lines: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_blanks: Option<u64>,
+    /// Lines of test code, and all other lines.
+    pub test_lines: u64,
+    pub non_test_lines: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub markers: Option<MarkerCounts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_length: Option<LineLengths>,
+    /// Lines per file over all files.
+    pub distribution: Distribution,
+    pub histogram: Vec<Bucket>,
     pub by_extension: Vec<ExtensionSummary>,
+    /// The files left out by `--dedup`, grouped by the file that was counted.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub duplicates: Vec<DuplicateGroup>,
+    /// The generated and vendored files left out of the totals.
+    #[serde(skip_serializing_if = "Excluded::is_empty")]
+    pub excluded: Excluded,
+}
+
+/// The `extension` of the row rolling up the extensions left out by `--top-ext`.
+pub const OTHER_EXTENSIONS: &str = "(other)";
+
+impl CountResult {
+    /// The `keep` extensions with the most lines, followed by a row rolling up
+    /// the remaining ones if there are any.
+    pub fn top_extensions(&self, keep: usize) -> Vec<ExtensionSummary> {
+        if self.by_extension.len() <= keep {
+            return self.by_extension.clone();
+        }
+        let (top, rest) = self.by_extension.split_at(keep);
+        let rolled_up: HashSet<&str> = rest.iter().map(|e| e.extension.as_str()).collect();
+        let files = self
+            .files
+            .iter()
+            .filter(|f| rolled_up.contains(f.extension.as_str()));
+
+        let mut markers = self
+            .markers
+            .as_ref()
+            .map(|total| total.keys().map(|marker| (marker.clone(), 0)).collect());
+        for ext in rest {
+            add_markers(&mut markers, ext.markers.as_ref());
+        }
+
+        let other = ExtensionSummary {
+            extension: OTHER_EXTENSIONS.to_string(),
+            file_count: rest.iter().map(|e| e.file_count).sum(),
+            total_lines: rest.iter().map(|e| e.total_lines).sum(),
+            total_bytes: rest.iter().map(|e| e.total_bytes).sum(),
+            total_chars: rest.iter().map(|e| e.total_chars).sum(),
+            total_words: rest.iter().map(|e| e.total_words).sum(),
+            total_code: rest.iter().map(|e| e.total_code).sum(),
+            total_comments: rest.iter().map(|e| e.total_comments).sum(),
+            total_doc_lines: rest.iter().map(|e| e.total_doc_lines).sum(),
+            total_blanks: rest.iter().map(|e| e.total_blanks).sum(),
+            markers,
+            line_fraction: rest.iter().map(|e| e.line_fraction).sum(),
+            file_fraction: rest.iter().map(|e| e.file_fraction).sum(),
+            distribution: Distribution::new(files),
+            rolled_up: Some(rest.iter().map(|e| e.extension.clone()).collect()),
+        };
+        top.iter().cloned().chain([other]).collect()
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -31,6 +242,16 @@ pub enum SortOrder {
     None,
 }
 
+/// Which files the totals and breakdowns are computed over.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum TotalsScope {
+    /// Only the files that pass `min_lines` and `max_lines`.
+    #[default]
+    Filtered,
+    /// Every counted file, even those left out of the file list.
+    All,
+}
+
 #[derive(Debug, Clone)]
 pub struct CountConfig {
     pub paths: Vec<PathBuf>,
@@ -38,4 +259,58 @@ pub struct CountConfig {
     pub globs: Vec<String>,
     pub use_ignore: bool,
     pub sort: SortOrder,
+    /// Count exactly these files instead of walking `paths`.
+    pub file_list: Option<Vec<PathBuf>>,
+    /// Name reported for content read from stdin (a `-` path).
+    pub stdin_name: Option<PathBuf>,
+    /// Language of content read from stdin, used to group it by extension.
+    pub stdin_language: Option<&'static Language>,
+    /// Count the entries of tar, tar.gz and zip archives instead of the archives.
+    pub archives: bool,
+    /// Count the uncompressed lines of gzip, zstd, bzip2 and xz files.
+    pub decompress: bool,
+    /// Which of bytes, characters and words to count alongside lines.
+    pub metrics: Metrics,
+    /// Only report files with at least this many lines.
+    pub min_lines: Option<u64>,
+    /// Only report files with at most this many lines.
+    pub max_lines: Option<u64>,
+    pub totals: TotalsScope,
+    /// Skip files larger than this many bytes.
+    pub max_filesize: Option<u64>,
+    /// Skip files last modified before this time.
+    pub newer_than: Option<SystemTime>,
+    /// Skip files last modified at or after this time.
+    pub older_than: Option<SystemTime>,
+    /// Descend at most this many directories below each path; 1 counts only
+    /// the files directly inside it.
+    pub max_depth: Option<usize>,
+    pub follow_symlinks: bool,
+    /// Don't descend into directories on other file systems than their path.
+    pub one_file_system: bool,
+    /// Count files with the same content, or that are the same file, once.
+    pub dedup: bool,
+    /// Count files that look generated instead of leaving them out.
+    pub include_generated: bool,
+    /// Count files in vendored directories instead of leaving them out.
+    pub include_vendored: bool,
+    /// Leave out files that look minified.
+    pub skip_minified: bool,
+}
+
+impl CountConfig {
+    /// Whether a file passes `min_lines` and `max_lines`.
+    pub fn in_line_range(&self, file: &FileCount) -> bool {
+        self.min_lines.is_none_or(|min| file.lines >= min)
+            && self.max_lines.is_none_or(|max| file.lines <= max)
+    }
+
+    /// Whether `-` was passed as a path.
+    pub fn reads_stdin(&self) -> bool {
+        self.paths.iter().any(|p| is_stdin(p))
+    }
+}
+
+pub(crate) fn is_stdin(path: &Path) -> bool {
+    path.as_os_str() == "-"
 }
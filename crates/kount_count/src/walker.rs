@@ -1,26 +1,247 @@
+use std::cmp::Reverse;
 use std::collections::HashMap;
+use std::fs::{self, Metadata};
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
 
-use ignore::overrides::OverrideBuilder;
+use ignore::overrides::{Override, OverrideBuilder};
 use ignore::WalkBuilder;
 use rayon::prelude::*;
 
-use crate::counter::count_lines;
-use crate::types::{CountConfig, CountResult, ExtensionSummary, FileCount, SortOrder};
+use crate::archive::{count_archive, ArchiveKind, SUFFIXES as ARCHIVE_SUFFIXES};
+use crate::counter::{
+    count_lines, count_lines_decompressed, count_reader, Counts, LineLengths, Metrics,
+};
+use crate::decompress::{Compression, EXTENSIONS as COMPRESSED_EXTENSIONS};
+use crate::dedup::{dedup, DuplicateGroup, HashingReader};
+use crate::detect::{exclude, Excluded};
+use crate::markers::{add_markers, count_markers};
+use crate::notebook::{count_notebook, is_notebook};
+use crate::stats::{histogram, Distribution};
+use crate::test_code::is_test_path;
+use crate::types::{
+    extension_of, is_stdin, CountConfig, CountResult, ExtensionSummary, FileCount, SortOrder,
+    TotalsScope,
+};
 
 pub fn count(config: &CountConfig) -> CountResult {
-    let paths = if config.paths.is_empty() {
+    let paths = collect_paths(config);
+    let mut files = count_files(&paths, config);
+    if config.reads_stdin() {
+        match count_stdin(config) {
+            Ok(file) => files.push(file),
+            Err(e) => eprintln!("warning: <stdin>: {e}"),
+        }
+    }
+    let (files, excluded, duplicates) = drop_excluded(files, config);
+    CountResult {
+        duplicates,
+        excluded,
+        ..summarize(files, config)
+    }
+}
+
+/// Drop the generated and vendored files `config` doesn't include, then the
+/// duplicates if `config.dedup` is set.
+pub(crate) fn drop_excluded(
+    mut files: Vec<FileCount>,
+    config: &CountConfig,
+) -> (Vec<FileCount>, Excluded, Vec<DuplicateGroup>) {
+    let excluded = exclude(&mut files, config);
+    let (files, duplicates) = if config.dedup {
+        dedup(files)
+    } else {
+        (files, Vec::new())
+    };
+    (files, excluded, duplicates)
+}
+
+/// Returns the paths `config` asks for, falling back to the current directory.
+/// `-` is left out, since stdin is counted separately.
+pub(crate) fn root_paths(config: &CountConfig) -> Vec<PathBuf> {
+    if config.paths.is_empty() {
         vec![std::env::current_dir().unwrap_or_else(|_| ".".into())]
     } else {
-        config.paths.clone()
+        config
+            .paths
+            .iter()
+            .filter(|path| !is_stdin(path))
+            .cloned()
+            .collect()
+    }
+}
+
+/// The part of `path` below the deepest of `roots` it is in, so that the
+/// directories above a walk don't mark what's in it as tests or vendored.
+pub(crate) fn below_root<'a>(path: &'a Path, roots: &[PathBuf]) -> &'a Path {
+    roots
+        .iter()
+        .filter_map(|root| path.strip_prefix(root).ok())
+        .min_by_key(|rel| rel.components().count())
+        .unwrap_or(path)
+}
+
+/// Count the content piped on stdin, named after `--stdin-name` if given,
+/// hashing it for `--dedup` since it can't be read again.
+fn count_stdin(config: &CountConfig) -> io::Result<FileCount> {
+    let path = config
+        .stdin_name
+        .clone()
+        .unwrap_or_else(|| PathBuf::from("<stdin>"));
+    let metrics = match config.stdin_language {
+        Some(language) => Metrics {
+            language: Some(language),
+            ..config.metrics.clone()
+        },
+        None => config.metrics.for_path(&path),
+    };
+    let (counts, content) = if config.dedup {
+        let mut reader = HashingReader::new(io::stdin().lock());
+        let counts = count_reader(&mut reader, &metrics)?;
+        (counts, Some(reader.finish()))
+    } else {
+        (count_reader(io::stdin().lock(), &metrics)?, None)
+    };
+    let extension = match config.stdin_language {
+        Some(language) => language.extension().to_string(),
+        None => extension_of(&path),
     };
+    Ok(FileCount {
+        extension,
+        content,
+        ..FileCount::new(path, counts)
+    })
+}
+
+/// Read a list of paths, one per line or NUL-separated, skipping empty entries.
+pub fn read_file_list(reader: impl BufRead, null_separated: bool) -> io::Result<Vec<PathBuf>> {
+    let separator = if null_separated { b'\0' } else { b'\n' };
+    let mut paths = Vec::new();
+    for entry in reader.split(separator) {
+        let mut entry = entry?;
+        if !null_separated && entry.last() == Some(&b'\r') {
+            entry.pop();
+        }
+        if !entry.is_empty() {
+            paths.push(path_from_bytes(entry));
+        }
+    }
+    Ok(paths)
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+    std::ffi::OsString::from_vec(bytes).into()
+}
+
+#[cfg(not(unix))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    String::from_utf8_lossy(&bytes).into_owned().into()
+}
+
+/// Build the `--ext`/`--glob` overrides, if any were requested.
+fn build_overrides(root: &Path, config: &CountConfig) -> Option<Override> {
+    overrides_with(root, config, |_| {})
+}
+
+/// Build the overrides the walk applies: the `--ext`/`--glob` filters, also
+/// letting archives through when they are opened, so that their entries can
+/// be filtered instead, and matching `--ext` against the inner name of
+/// compressed files when they are decompressed.
+fn walk_overrides(root: &Path, config: &CountConfig) -> Option<Override> {
+    let whitelists =
+        !config.extensions.is_empty() || config.globs.iter().any(|glob| !glob.starts_with('!'));
+    overrides_with(root, config, |overrides| {
+        if config.archives && whitelists {
+            for (suffix, _) in ARCHIVE_SUFFIXES {
+                overrides.add(&format!("*{suffix}")).ok();
+            }
+        }
+        if config.decompress {
+            for ext in &config.extensions {
+                for compressed in COMPRESSED_EXTENSIONS {
+                    overrides.add(&format!("*.{ext}.{compressed}")).ok();
+                }
+            }
+        }
+    })
+}
+
+/// Build the `--ext`/`--glob` overrides after the patterns `add` adds, so
+/// that the `!` globs still apply to those.
+fn overrides_with(
+    root: &Path,
+    config: &CountConfig,
+    add: impl FnOnce(&mut OverrideBuilder),
+) -> Option<Override> {
+    if config.extensions.is_empty() && config.globs.is_empty() {
+        return None;
+    }
+    let mut overrides = OverrideBuilder::new(root);
+    add(&mut overrides);
+    for ext in &config.extensions {
+        overrides.add(&format!("*.{ext}")).ok();
+    }
+    for glob in &config.globs {
+        overrides.add(glob).ok();
+    }
+    overrides.build().ok()
+}
+
+/// Whether a file passes the size and modification-time filters, checked
+/// before it is read. Files whose metadata can't be read are let through.
+fn passes_metadata(config: &CountConfig, metadata: impl FnOnce() -> Option<Metadata>) -> bool {
+    if config.max_filesize.is_none() && config.newer_than.is_none() && config.older_than.is_none() {
+        return true;
+    }
+    let Some(metadata) = metadata() else {
+        return true;
+    };
+    let modified = metadata.modified().ok();
+    config.max_filesize.is_none_or(|max| metadata.len() <= max)
+        && config
+            .newer_than
+            .is_none_or(|time| modified.is_some_and(|m| m >= time))
+        && config
+            .older_than
+            .is_none_or(|time| modified.is_some_and(|m| m < time))
+}
+
+/// Phase 1: Walk the configured paths and collect every file that passes the
+/// ignore rules and filters.
+pub fn collect_paths(config: &CountConfig) -> Vec<PathBuf> {
+    // An explicit file list bypasses the walk, but still honours the filters
+    if let Some(list) = &config.file_list {
+        let overrides = walk_overrides(Path::new("."), config);
+        return list
+            .iter()
+            .filter(|path| {
+                overrides
+                    .as_ref()
+                    .is_none_or(|o| !o.matched(path, false).is_ignore())
+            })
+            .filter(|path| passes_metadata(config, || fs::metadata(path).ok()))
+            .cloned()
+            .collect();
+    }
+
+    let paths = root_paths(config);
 
     // Build walker from first path, add the rest
-    let first = &paths[0];
+    let Some(first) = paths.first() else {
+        return Vec::new();
+    };
     let mut builder = WalkBuilder::new(first);
     for path in &paths[1..] {
         builder.add(path);
     }
 
+    builder
+        .max_depth(config.max_depth)
+        .follow_links(config.follow_symlinks)
+        .same_file_system(config.one_file_system);
+
     if !config.use_ignore {
         builder
             .hidden(false)
@@ -31,87 +252,257 @@ pub fn count(config: &CountConfig) -> CountResult {
     }
 
     // Apply extension and glob filters
-    let has_filters = !config.extensions.is_empty() || !config.globs.is_empty();
-    if has_filters {
-        let mut overrides = OverrideBuilder::new(first);
-        for ext in &config.extensions {
-            overrides.add(&format!("*.{ext}")).ok();
-        }
-        for glob in &config.globs {
-            overrides.add(glob).ok();
-        }
-        if let Ok(built) = overrides.build() {
-            builder.overrides(built);
-        }
+    if let Some(overrides) = walk_overrides(first, config) {
+        builder.overrides(overrides);
     }
 
-    // Phase 1: Collect file paths
-    let entries: Vec<_> = builder
+    builder
         .build()
         .filter_map(|entry| {
-            let entry = entry.ok()?;
-            if entry.file_type()?.is_file() {
+            let entry = entry
+                .inspect_err(|e| {
+                    if is_loop(e) {
+                        eprintln!("warning: {e}");
+                    }
+                })
+                .ok()?;
+            if entry.file_type()?.is_file() && passes_metadata(config, || entry.metadata().ok()) {
                 Some(entry.into_path())
             } else {
                 None
             }
         })
-        .collect();
+        .collect()
+}
+
+/// Whether a walk error is a symlink pointing back at one of its ancestors.
+/// Those are reported, other walk errors are skipped silently.
+fn is_loop(error: &ignore::Error) -> bool {
+    match error {
+        ignore::Error::Loop { .. } => true,
+        ignore::Error::WithPath { err, .. }
+        | ignore::Error::WithDepth { err, .. }
+        | ignore::Error::WithLineNumber { err, .. } => is_loop(err),
+        ignore::Error::Partial(errors) => errors.iter().any(is_loop),
+        _ => false,
+    }
+}
 
-    // Phase 2: Count lines in parallel
-    let files: Vec<FileCount> = entries
+/// Phase 2: Count lines in parallel
+pub fn count_files(paths: &[PathBuf], config: &CountConfig) -> Vec<FileCount> {
+    let overrides = entry_overrides(config);
+    paths
         .par_iter()
-        .filter_map(|path| match count_lines(path) {
-            Ok(lines) => Some(FileCount {
-                path: path.clone(),
-                lines,
-            }),
-            Err(e) => {
-                eprintln!("warning: {}: {e}", path.display());
-                None
-            }
-        })
-        .collect();
+        .flat_map_iter(|path| count_path(path, config, overrides.as_ref()))
+        .collect()
+}
+
+/// The filters applied to entries inside archives.
+pub(crate) fn entry_overrides(config: &CountConfig) -> Option<Override> {
+    config
+        .archives
+        .then(|| build_overrides(Path::new("."), config))
+        .flatten()
+}
+
+/// Count a single walked path, which yields several files for an archive.
+pub(crate) fn count_path(
+    path: &Path,
+    config: &CountConfig,
+    overrides: Option<&Override>,
+) -> Vec<FileCount> {
+    let archive = ArchiveKind::from_path(path).filter(|_| config.archives);
+    let compression = Compression::from_path(path).filter(|_| config.decompress);
+    let counted = match (archive, compression) {
+        (Some(kind), _) => {
+            count_archive(path, kind, overrides, config.max_filesize, &config.metrics)
+        }
+        (None, Some(compression)) => {
+            let metrics = config.metrics.for_path(&path.with_extension(""));
+            count_lines_decompressed(path, compression, &metrics).map(|counts| {
+                // Group by the inner extension: `app.log.gz` counts as `log`
+                vec![FileCount {
+                    extension: extension_of(&path.with_extension("")),
+                    decompressed: true,
+                    ..FileCount::new(path.to_path_buf(), counts)
+                }]
+            })
+        }
+        (None, None) => count_document(path, &config.metrics.for_path(path))
+            .map(|counts| vec![FileCount::new(path.to_path_buf(), counts)]),
+    };
+    counted.unwrap_or_else(|e| {
+        eprintln!("warning: {}: {e}", path.display());
+        Vec::new()
+    })
+}
 
-    // Phase 3: Sort results
-    let mut files = files;
+/// Count a file along with the languages embedded in it. Notebooks that can't
+/// be parsed are counted as the JSON they are.
+fn count_document(path: &Path, metrics: &Metrics) -> io::Result<Counts> {
+    if is_notebook(path) {
+        if let Ok(counts) = count_notebook(path, metrics) {
+            return Ok(counts);
+        }
+    }
+    count_lines(path, metrics)
+}
+
+/// Phase 3: Sort the counted files and compute totals.
+pub fn summarize(mut files: Vec<FileCount>, config: &CountConfig) -> CountResult {
+    if config.totals == TotalsScope::Filtered {
+        files.retain(|f| config.in_line_range(f));
+    }
     match config.sort {
-        SortOrder::Lines => files.sort_by(|a, b| b.lines.cmp(&a.lines)),
+        SortOrder::Lines => files.sort_by_key(|f| Reverse(f.lines)),
         SortOrder::Name => files.sort_by(|a, b| a.path.cmp(&b.path)),
         SortOrder::None => {}
     }
 
     // Compute totals
+    let metrics = &config.metrics;
     let total_lines = files.iter().map(|f| f.lines).sum();
     let total_files = files.len() as u64;
+    let roots = root_paths(config);
+    for file in &mut files {
+        file.line_fraction = fraction(file.lines, total_lines);
+        if is_test_path(below_root(&file.path, &roots)) {
+            file.test_lines = file.lines;
+        }
+    }
+    let total_bytes = metrics
+        .bytes
+        .then(|| files.iter().filter_map(|f| f.bytes).sum());
+    let total_chars = metrics
+        .chars
+        .then(|| files.iter().filter_map(|f| f.chars).sum());
+    let total_words = metrics
+        .words
+        .then(|| files.iter().filter_map(|f| f.words).sum());
+    let total_code = metrics
+        .classify
+        .then(|| files.iter().filter_map(|f| f.code).sum());
+    let total_comments = metrics
+        .classify
+        .then(|| files.iter().filter_map(|f| f.comments).sum());
+    let total_doc_lines = metrics
+        .classify
+        .then(|| files.iter().filter_map(|f| f.doc_lines).sum());
+    let total_blanks = metrics
+        .classify
+        .then(|| files.iter().filter_map(|f| f.blanks).sum());
+    let test_lines = files.iter().map(|f| f.test_lines).sum();
+    let mut markers = metrics
+        .markers
+        .as_ref()
+        .map(|markers| count_markers(markers, &[]));
+    for file in &files {
+        add_markers(&mut markers, file.markers.as_ref());
+    }
+    let line_length = metrics.max_line_length.map(|limit| {
+        let lengths = files.iter().filter_map(|f| f.line_length);
+        lengths.fold(LineLengths::new(0, limit, 0, 0, 0), |total, file| {
+            LineLengths::new(
+                total.max.max(file.max),
+                limit,
+                total.over_limit + file.over_limit,
+                total.chars + file.chars,
+                total.lines + file.lines,
+            )
+        })
+    });
 
     // Per-extension breakdown
-    let mut ext_map: HashMap<String, (u64, u64)> = HashMap::new();
+    let mut ext_map: HashMap<String, ExtensionSummary> = HashMap::new();
+    let mut ext_files: HashMap<&str, Vec<&FileCount>> = HashMap::new();
     for file in &files {
-        let ext = file
-            .path
-            .extension()
-            .and_then(|e| e.to_str())
-            .unwrap_or("")
-            .to_string();
-        let entry = ext_map.entry(ext).or_default();
-        entry.0 += 1;
-        entry.1 += file.lines;
-    }
-    let mut by_extension: Vec<ExtensionSummary> = ext_map
-        .into_iter()
-        .map(|(extension, (file_count, total_lines))| ExtensionSummary {
-            extension,
-            file_count,
-            total_lines,
-        })
-        .collect();
-    by_extension.sort_by(|a, b| b.total_lines.cmp(&a.total_lines));
+        let entry = ext_map
+            .entry(file.extension.clone())
+            .or_insert_with(|| ExtensionSummary {
+                extension: file.extension.clone(),
+                file_count: 0,
+                total_lines: 0,
+                total_bytes: metrics.bytes.then_some(0),
+                total_chars: metrics.chars.then_some(0),
+                total_words: metrics.words.then_some(0),
+                total_code: metrics.classify.then_some(0),
+                total_comments: metrics.classify.then_some(0),
+                total_doc_lines: metrics.classify.then_some(0),
+                total_blanks: metrics.classify.then_some(0),
+                markers: metrics
+                    .markers
+                    .as_ref()
+                    .map(|markers| count_markers(markers, &[])),
+                line_fraction: 0.0,
+                file_fraction: 0.0,
+                distribution: Distribution::default(),
+                rolled_up: None,
+            });
+        entry.file_count += 1;
+        entry.total_lines += file.lines;
+        add(&mut entry.total_bytes, file.bytes);
+        add(&mut entry.total_chars, file.chars);
+        add(&mut entry.total_words, file.words);
+        add(&mut entry.total_code, file.code);
+        add(&mut entry.total_comments, file.comments);
+        add(&mut entry.total_doc_lines, file.doc_lines);
+        add(&mut entry.total_blanks, file.blanks);
+        add_markers(&mut entry.markers, file.markers.as_ref());
+        ext_files.entry(&file.extension).or_default().push(file);
+    }
+    for summary in ext_map.values_mut() {
+        summary.line_fraction = fraction(summary.total_lines, total_lines);
+        summary.file_fraction = fraction(summary.file_count, total_files);
+        let members = ext_files
+            .remove(summary.extension.as_str())
+            .unwrap_or_default();
+        summary.distribution = Distribution::new(members);
+    }
+    let mut by_extension: Vec<ExtensionSummary> = ext_map.into_values().collect();
+    by_extension.sort_by(|a, b| {
+        (Reverse(a.total_lines), &a.extension).cmp(&(Reverse(b.total_lines), &b.extension))
+    });
+
+    let distribution = Distribution::new(&files);
+    let histogram = histogram(&files);
+    if config.totals == TotalsScope::All {
+        files.retain(|f| config.in_line_range(f));
+    }
 
     CountResult {
         files,
         total_lines,
         total_files,
+        total_bytes,
+        total_chars,
+        total_words,
+        total_code,
+        total_comments,
+        total_doc_lines,
+        total_blanks,
+        test_lines,
+        non_test_lines: total_lines - test_lines,
+        markers,
+        line_length,
+        distribution,
+        histogram,
         by_extension,
+        duplicates: Vec::new(),
+        excluded: Excluded::default(),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn fraction(part: u64, whole: u64) -> f64 {
+    if whole == 0 {
+        0.0
+    } else {
+        part as f64 / whole as f64
+    }
+}
+
+fn add(total: &mut Option<u64>, value: Option<u64>) {
+    if let (Some(total), Some(value)) = (total, value) {
+        *total += value;
     }
 }
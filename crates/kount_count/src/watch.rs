@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{Event, RecursiveMode, Watcher};
+use rayon::prelude::*;
+
+use crate::types::{CountConfig, CountResult, FileCount};
+use crate::walker::{
+    collect_paths, count_path, drop_excluded, entry_overrides, root_paths, summarize,
+};
+
+/// How long to keep collecting events after the first one before recounting.
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Count `config` once, then keep recounting on filesystem changes until
+/// `on_update` fails.
+///
+/// `on_update` receives the current result and the result of the initial
+/// count. Only files touched by an event (or newly matched by the walk) are
+/// re-read; the walk itself is repeated so ignore rules and filters apply to
+/// events exactly as they do to the initial count.
+pub fn watch<F>(config: &CountConfig, mut on_update: F) -> io::Result<()>
+where
+    F: FnMut(&CountResult, &CountResult) -> io::Result<()>,
+{
+    let roots: Vec<(PathBuf, PathBuf)> = root_paths(config)
+        .into_iter()
+        .filter_map(|root| Some((root.canonicalize().ok()?, root)))
+        .collect();
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(io::Error::other)?;
+    for (abs, _) in &roots {
+        watcher
+            .watch(abs, RecursiveMode::Recursive)
+            .map_err(io::Error::other)?;
+    }
+
+    // Counts are keyed by the walked path, since an archive yields several files
+    let overrides = entry_overrides(config);
+    let count = |paths: Vec<PathBuf>| -> Vec<(PathBuf, Vec<FileCount>)> {
+        paths
+            .into_par_iter()
+            .map(|path| {
+                let counted = count_path(&path, config, overrides.as_ref());
+                (path, counted)
+            })
+            .collect()
+    };
+    let flatten = |files: &HashMap<PathBuf, Vec<FileCount>>| -> Vec<FileCount> {
+        files.values().flatten().cloned().collect()
+    };
+
+    let mut files: HashMap<PathBuf, Vec<FileCount>> =
+        count(collect_paths(config)).into_iter().collect();
+    let baseline = summarize_counted(flatten(&files), config);
+    on_update(&baseline, &baseline)?;
+
+    while let Ok(event) = rx.recv() {
+        let mut changed = HashSet::new();
+        collect_event(event, &roots, &mut changed);
+        while let Ok(event) = rx.recv_timeout(DEBOUNCE) {
+            collect_event(event, &roots, &mut changed);
+        }
+
+        let eligible: HashSet<PathBuf> = collect_paths(config).into_iter().collect();
+        let before = files.len();
+        files.retain(|path, _| eligible.contains(path));
+        let removed = before != files.len();
+
+        let stale: Vec<PathBuf> = eligible
+            .into_iter()
+            .filter(|path| changed.contains(path) || !files.contains_key(path))
+            .collect();
+        if stale.is_empty() && !removed {
+            continue;
+        }
+
+        files.extend(count(stale));
+
+        let result = summarize_counted(flatten(&files), config);
+        on_update(&result, &baseline)?;
+    }
+
+    Ok(())
+}
+
+fn summarize_counted(files: Vec<FileCount>, config: &CountConfig) -> CountResult {
+    let (files, excluded, duplicates) = drop_excluded(files, config);
+    CountResult {
+        duplicates,
+        excluded,
+        ..summarize(files, config)
+    }
+}
+
+/// Map the absolute paths of an event back onto the paths the walker yields.
+fn collect_event(
+    event: notify::Result<Event>,
+    roots: &[(PathBuf, PathBuf)],
+    changed: &mut HashSet<PathBuf>,
+) {
+    let event = match event {
+        Ok(event) => event,
+        Err(e) => {
+            eprintln!("warning: {e}");
+            return;
+        }
+    };
+    for path in event.paths {
+        if let Some(path) = relative_to_roots(&path, roots) {
+            changed.insert(path);
+        }
+    }
+}
+
+fn relative_to_roots(path: &Path, roots: &[(PathBuf, PathBuf)]) -> Option<PathBuf> {
+    roots.iter().find_map(|(abs, root)| {
+        let rel = path.strip_prefix(abs).ok()?;
+        if rel.as_os_str().is_empty() {
+            Some(root.clone())
+        } else {
+            Some(root.join(rel))
+        }
+    })
+}
@@ -0,0 +1,122 @@
+//! Benchmark the line counting strategies of `kount_count::counter`.
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use anyhow::{bail, Result};
+use kount_count::counter::{count_file, Metrics, Strategy};
+
+const STRATEGIES: [Strategy; 3] = [Strategy::Buffered, Strategy::Mmap, Strategy::ParallelMmap];
+
+#[derive(clap::Args)]
+pub struct CliArgs {
+    /// Size of the generated file in MiB.
+    #[arg(long, default_value_t = 512)]
+    pub(crate) size_mib: u64,
+
+    /// Number of timed runs per strategy; the fastest one is reported.
+    #[arg(long, default_value_t = 5)]
+    pub(crate) runs: u32,
+
+    /// Benchmark an existing file instead of generating one.
+    #[arg(long)]
+    pub(crate) file: Option<PathBuf>,
+}
+
+pub(crate) fn main(args: &CliArgs) -> Result<()> {
+    let generated;
+    let path = if let Some(path) = &args.file {
+        path.as_path()
+    } else {
+        generated = generate(args.size_mib)?;
+        generated.path()
+    };
+    let size = fs_err::metadata(path)?.len();
+
+    println!(
+        "{} ({:.1} MiB, best of {}, {} threads)",
+        path.display(),
+        mib(size),
+        args.runs,
+        rayon::current_num_threads()
+    );
+
+    let mut expected = None;
+    for strategy in STRATEGIES {
+        let (lines, best) = time_strategy(path, strategy, args.runs)?;
+        println!(
+            "  {:<14} {:>10.1} ms  {:>8.0} MiB/s  {lines} lines",
+            format!("{strategy:?}"),
+            best.as_secs_f64() * 1000.0,
+            mib(size) / best.as_secs_f64()
+        );
+        match expected {
+            None => expected = Some(lines),
+            Some(expected) if expected != lines => {
+                bail!("{strategy:?} counted {lines} lines, expected {expected}");
+            }
+            Some(_) => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn time_strategy(path: &Path, strategy: Strategy, runs: u32) -> Result<(u64, Duration)> {
+    let mut best = Duration::MAX;
+    let mut lines = 0;
+    for _ in 0..runs.max(1) {
+        // Reopen each run, the buffered strategy reads from the current position
+        let file = File::open(path)?;
+        let start = Instant::now();
+        lines = count_file(&file, strategy, &Metrics::default())?.lines;
+        best = best.min(start.elapsed());
+    }
+    Ok((lines, best))
+}
+
+/// Write a CSV-like file of roughly `size_mib` MiB.
+fn generate(size_mib: u64) -> Result<tempfile::NamedTempFile> {
+    let mut file = tempfile::Builder::new()
+        .prefix("kount-bench")
+        .suffix(".csv")
+        .tempfile()?;
+    let target = size_mib << 20;
+    let mut written = 0u64;
+    let mut out = BufWriter::new(file.as_file_mut());
+    let mut row = 0u64;
+    while written < target {
+        let line = format!("{row},kount,{},{},benchmark row\n", row * 7, row % 13);
+        out.write_all(line.as_bytes())?;
+        written += line.len() as u64;
+        row += 1;
+    }
+    out.flush()?;
+    drop(out);
+    Ok(file)
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn mib(bytes: u64) -> f64 {
+    bytes as f64 / f64::from(1u32 << 20)
+}
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+
+    use super::{generate, time_strategy, STRATEGIES};
+
+    #[test]
+    fn strategies_agree() -> Result<()> {
+        let file = generate(2)?;
+        let counts = STRATEGIES
+            .iter()
+            .map(|strategy| Ok(time_strategy(file.path(), *strategy, 1)?.0))
+            .collect::<Result<Vec<_>>>()?;
+        assert!(counts[0] > 0);
+        assert!(counts.iter().all(|count| *count == counts[0]));
+        Ok(())
+    }
+}
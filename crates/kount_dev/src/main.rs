@@ -9,6 +9,7 @@ use std::process::ExitCode;
 use anyhow::Result;
 use clap::{Parser, Subcommand};
 
+mod bench_count;
 mod generate_cli_reference;
 
 const ROOT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../../");
@@ -42,6 +43,8 @@ enum Command {
     GenerateCliReference(generate_cli_reference::CliArgs),
     /// Generate all developer documentation and references.
     GenerateAll,
+    /// Benchmark the line counting strategies on a large file.
+    BenchCount(bench_count::CliArgs),
 }
 
 fn main() -> Result<ExitCode> {
@@ -51,6 +54,7 @@ fn main() -> Result<ExitCode> {
         Command::GenerateAll => {
             generate_cli_reference::main(&generate_cli_reference::CliArgs { mode: Mode::Write })?;
         }
+        Command::BenchCount(args) => bench_count::main(&args)?,
     }
     Ok(ExitCode::SUCCESS)
 }